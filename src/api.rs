@@ -1,4 +1,4 @@
-use std::{collections::HashMap, ops::Div};
+use std::{collections::HashMap, sync::Arc};
 
 use anyhow::anyhow;
 use chrono::{DateTime, Datelike, Local};
@@ -9,9 +9,10 @@ use reqwest::{
 use serde::Serialize;
 
 use crate::{
-    config::Configuration,
+    cache::Cache,
+    config::{Configuration, ContractVersion},
     login::{self, Credential},
-    time::{parse_date, FreeDay, HalfDay},
+    time::{parse_date, split_at_midnight, FreeDay, FreeDayReason, HalfDay, LeaveKind, Shift},
 };
 
 pub enum ApiEndpoint {
@@ -21,6 +22,8 @@ pub enum ApiEndpoint {
     ClockOut,
     Shifts,
     Leaves,
+    LeaveTypes,
+    Allowances,
     Holidays,
     Companies,
     Employees,
@@ -39,6 +42,8 @@ impl ApiEndpoint {
             Self::ClockIn => base_url + "/attendance/shifts/clock_in/",
             Self::ClockOut => base_url + "/attendance/shifts/clock_out/",
             Self::Leaves => base_url + "/leaves/",
+            Self::LeaveTypes => base_url + "/leave_types/",
+            Self::Allowances => base_url + "/allowances/",
             Self::Holidays => base_url + "/company_holidays/",
             Self::Companies => base_url + "/companies/",
             Self::Employees => base_url + "/employees/",
@@ -48,8 +53,11 @@ impl ApiEndpoint {
 }
 
 /// Provides methods to make calls to the Factorial API
+#[derive(Clone)]
 pub struct FactorialApi {
     client: blocking::Client,
+    /// Shared by all copies of the api
+    cache: Arc<Cache>,
     pub config: Configuration,
 }
 
@@ -66,6 +74,7 @@ impl FactorialApi {
     ) -> anyhow::Result<FactorialApi> {
         // Attempt to login to Factorial
         let client = credential.authenticate_client()?;
+        let cache = Cache::from_config(config);
         if config.user_id == "" {
             // Sets factorial_data cookie which also contains the users access_id
             let response = client.get(ApiEndpoint::Companies.url()).send()?;
@@ -81,8 +90,11 @@ impl FactorialApi {
                 }
             }
             // Get a list of all employees
-            let response = client.get(ApiEndpoint::Employees.url()).send()?;
-            let emloyees: Vec<serde_json::Value> = response.json()?;
+            let emloyees: Vec<serde_json::Value> = serde_json::from_value(
+                cache.get_or_fetch(&format!("employees/{}", config.email), || {
+                    Ok(client.get(ApiEndpoint::Employees.url()).send()?.json()?)
+                })?,
+            )?;
             // Get the employee with your access_id
             for employee in emloyees {
                 if employee["access_id"].to_string() == access_id {
@@ -91,38 +103,28 @@ impl FactorialApi {
             }
         }
 
-        if config.working_hours == 0.0 {
-            let response = client
-                .get(ApiEndpoint::Contracts.url())
-                .query(&[("employee_ids[]", &config.user_id)])
-                .send()?;
-            let mut contracts: Vec<serde_json::Value> = response.json().unwrap();
-            if contracts.len() == 0 {
-                return Err(anyhow!("The employee has no contract. Unable to get the amount of working hours. Manually setting the amount in the configuration file can bypass this issue."));
-            }
-            let hours_string = contracts.pop().unwrap()["working_hours"].to_string();
-            // breaks if working_hours_frequency is not "weekly"
-            if hours_string.len() != 4 {
-                return Err(anyhow!("The amount of working hours is either in a format that cannot be parsed or you work an unusual amount of hours a week"));
+        // Contract versions rarely change, so they are only retrieved once a day to pick up a new
+        // version. The versions in the configuration are used if that fails.
+        let today = Local::now().date_naive();
+        if config.contracts_outdated(today) {
+            match get_contract_versions(&client, &cache, &config.user_id) {
+                Ok(contracts) => {
+                    if let Some(previous) = config.contracts.last().cloned() {
+                        config.follow_contract(&previous, contracts.last().unwrap());
+                    }
+                    config.contracts = contracts;
+                    config.contracts_checked_on = Some(today);
+                }
+                Err(e) if config.working_hours == 0.0 => return Err(e),
+                Err(_) => {}
             }
-            let hours_float = format!(
-                "{}.{}",
-                hours_string.get(0..2).unwrap(),
-                hours_string.get(2..4).unwrap()
-            )
-            .parse::<f32>()?;
-            config.working_hours = hours_float;
-
-            // set working_week_days
-            let days_string = &contracts.pop().unwrap()["working_week_days"]
-                .as_str()
-                .unwrap()
-                .to_owned();
-            let days: Vec<String> = days_string.split(',').map(|s| s.to_owned()).collect();
-            config.working_week_days = days;
-            config.shift_duration = config
-                .working_hours
-                .div(config.working_week_days.len() as f32);
+        }
+        // Manually set working hours take precedence over the contract.
+        if config.working_hours == 0.0 {
+            let current = config.contracts.last().unwrap().clone();
+            config.working_hours = current.weekly_hours();
+            config.shift_duration = current.daily_hours();
+            config.working_week_days = current.working_week_days;
         }
 
         config
@@ -131,6 +133,7 @@ impl FactorialApi {
 
         Ok(FactorialApi {
             client,
+            cache: Arc::new(cache),
             config: config.clone(),
         })
     }
@@ -163,6 +166,14 @@ impl FactorialApi {
         ))
     }
 
+    /// Get a copy of the api that creates shifts and breaks at the given location instead of the
+    /// configured one.
+    pub fn with_location_type(&self, location_type: &str) -> FactorialApi {
+        let mut api = self.clone();
+        api.config.location_type = location_type.to_string();
+        api
+    }
+
     /// Starts a shift at the given time.
     /// # Errors
     /// Returns an error if:
@@ -202,7 +213,7 @@ impl FactorialApi {
         match response.status() {
             StatusCode::CREATED => Ok(()),
             StatusCode::CONFLICT => Err(anyhow!(
-                "There already in an ongoing break or no open shift to take a break from."
+                "There is either already an ongoing break or there is no open shift to take a break from."
             )),
             _ => Err(anyhow!("Could not start a break")),
         }
@@ -217,7 +228,7 @@ impl FactorialApi {
         let response = self.post_api_call(ApiEndpoint::BreakEnd, time)?;
         match response.status() {
             StatusCode::OK => Ok(()),
-            _ => Err(anyhow!("Could not end break")),
+            _ => Err(anyhow!("There is no ongoing break.")),
         }
     }
 
@@ -226,42 +237,118 @@ impl FactorialApi {
     /// # Errors
     /// Returns an Error if the operation could not be completed.
     pub fn delete_all_shifts(&self, time: DateTime<Local>) -> anyhow::Result<()> {
-        let month = time.month();
-        let year = time.year();
-        let day = time.day();
-        let response = self
-            .client
-            .get(ApiEndpoint::Shifts.url())
-            .query(&[
-                ("employee_id", self.config.user_id.as_str()),
-                ("month", &month.to_string()),
-                ("year", &year.to_string()),
-            ])
-            .send()?;
-        let shifts: Vec<serde_json::Value> = response.json()?;
+        self.ensure_editable(time)?;
+        self.delete_shifts(&self.get_shifts(time, time)?)
+    }
+
+    /// Deletes the given shifts and breaks. Should one of them fail, the ones already deleted are
+    /// created again.
+    /// # Errors
+    /// Returns an Error if the attendance period of one of the shifts is already approved or a
+    /// shift could not be deleted.
+    pub fn delete_shifts(&self, shifts: &[Shift]) -> anyhow::Result<()> {
+        for shift in shifts {
+            self.ensure_editable(shift.start)?;
+        }
+        for (i, shift) in shifts.iter().enumerate() {
+            if let Err(err) = self.delete_shift(shift.id) {
+                if self.restore_shifts(&shifts[..i]).is_err() {
+                    return Err(anyhow!("{err} Restoring the shifts deleted so far failed."));
+                }
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates deleted shifts and breaks again. Open ones are started again.
+    /// # Errors
+    /// Returns an Error if one of the shifts or breaks could not be created.
+    pub fn restore_shifts(&self, shifts: &[Shift]) -> anyhow::Result<()> {
         for shift in shifts {
-            let shift_day = shift["day"]
-                .as_u64()
-                .expect("Factorial no longer stores the day as an integer.");
-            if shift_day == day as u64 {
-                let shift_id = shift["id"].as_u64().unwrap().to_string();
-                let response = self
-                    .client
-                    .delete(ApiEndpoint::Shifts.url() + &shift_id)
-                    .send()?;
-                if response.status() != StatusCode::NO_CONTENT {
-                    return Err(anyhow!(
-                        "Received {}. Should have receive 204",
-                        response.status()
-                    ));
+            match (shift.end, shift.is_break) {
+                (Some(end), false) => {
+                    self.make_shift(shift.start, end)?;
+                }
+                (Some(end), true) => {
+                    self.make_break(shift.start, end)?;
                 }
+                (None, false) => self.shift_start(shift.start)?,
+                (None, true) => self.break_start(shift.start)?,
             }
         }
         Ok(())
     }
 
+    /// Deletes the shift or break with the given id.
+    /// # Errors
+    /// Returns an Error if the request could not be sent or the shift could not be deleted.
+    pub fn delete_shift(&self, id: u64) -> anyhow::Result<()> {
+        let response = self
+            .client
+            .delete(ApiEndpoint::Shifts.url() + &id.to_string())
+            .send()?;
+        if response.status() != StatusCode::NO_CONTENT {
+            return Err(anyhow!(
+                "Received {}. Should have receive 204",
+                response.status()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Retrieves all shifts and breaks that started on one of the days from `from` until `to`.
+    /// # Errors
+    /// Returns an Error if:
+    /// - the request could not be sent
+    /// - the response body could not be parsed
+    pub fn get_shifts(
+        &self,
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+    ) -> anyhow::Result<Vec<Shift>> {
+        let (from, to) = (from.date_naive(), to.date_naive());
+        let mut shifts = Vec::new();
+        let mut month = from.with_day(1).unwrap();
+        while month <= to {
+            let response = self
+                .client
+                .get(ApiEndpoint::Shifts.url())
+                .query(&[
+                    ("employee_id", self.config.user_id.as_str()),
+                    ("month", &month.month().to_string()),
+                    ("year", &month.year().to_string()),
+                ])
+                .send()?;
+            let month_shifts: Vec<serde_json::Value> = response.json()?;
+            shifts.extend(
+                month_shifts
+                    .iter()
+                    .filter_map(parse_shift)
+                    .filter(|s| s.start.date_naive() >= from && s.start.date_naive() <= to),
+            );
+            month = month.checked_add_months(chrono::Months::new(1)).unwrap();
+        }
+        shifts.sort_by_key(|s| s.start);
+        Ok(shifts)
+    }
+
+    /// Retrieves the shifts and breaks that were started on one of the days before the day of
+    /// `time` but never closed. Only the last two weeks are searched.
+    /// # Errors
+    /// Returns an Error if the shifts could not be retrieved.
+    pub fn get_open_shifts(&self, time: DateTime<Local>) -> anyhow::Result<Vec<Shift>> {
+        let from = time.checked_sub_days(chrono::Days::new(14)).unwrap();
+        let to = time.checked_sub_days(chrono::Days::new(1)).unwrap();
+        Ok(self
+            .get_shifts(from, to)?
+            .into_iter()
+            .filter(|s| s.end.is_none())
+            .collect())
+    }
+
     /// Retrieves all days on which no work has to be done. Includes holidays, paid time off and
-    /// weekends.
+    /// weekends. Leaves that still wait for approval are included and marked as pending.
     /// # Errors
     /// Returns an Error if:
     /// - the request could not be sent
@@ -277,60 +364,92 @@ impl FactorialApi {
         let from_ymd = format!("{}", from.format("%Y-%m-%d"));
         let to_ymd = format!("{}", to.format("%Y-%m-%d"));
 
-        let response = self.client.get(ApiEndpoint::Holidays.url()).send()?;
-
-        let company_holidays: Vec<serde_json::Value> = response.json()?;
+        let company_holidays: Vec<serde_json::Value> = serde_json::from_value(
+            self.cache
+                .get_or_fetch(&format!("holidays/{}", self.config.user_id), || {
+                    Ok(self
+                        .client
+                        .get(ApiEndpoint::Holidays.url())
+                        .send()?
+                        .json()?)
+                })?,
+        )?;
 
         for holiday in company_holidays {
             let day = parse_date(holiday["date"].as_str().unwrap()).unwrap();
-            let half: HalfDay;
-            if holiday["half_day"].is_null() {
-                half = HalfDay::WholeDay;
-            } else if holiday["half_day"].as_str().unwrap() == "end_of_day" {
-                half = HalfDay::EndOfDay;
-            } else {
-                half = HalfDay::StartOfDay;
-            }
-            free_days.push(FreeDay { day, half })
+            let half = parse_half_day(&holiday["half_day"]);
+            let name = holiday["summary"]
+                .as_str()
+                .or(holiday["name"].as_str())
+                .unwrap_or("")
+                .to_string();
+            free_days.push(FreeDay {
+                day,
+                half,
+                hours: None,
+                reason: FreeDayReason::Holiday(name),
+            })
         }
 
+        // Leaves are requested regardless of their approval status, rejected ones are skipped and
+        // pending ones are marked as such.
         let response = self
             .client
             .get(ApiEndpoint::Leaves.url())
             .query(&[
                 ("employee_id", self.config.user_id.as_str()),
-                ("terminated", "true"),
                 ("from", from_ymd.as_str()),
                 ("to", to_ymd.as_str()),
             ])
             .send()?;
         let vacations: Vec<serde_json::Value> = response.json()?;
         for vacay in vacations {
+            let approved = match vacay["approved"].as_bool() {
+                Some(false) => continue,
+                Some(true) => true,
+                None => false,
+            };
+            let kind = LeaveKind::from_name(
+                vacay["leave_type_name"]
+                    .as_str()
+                    .or(vacay["leave_type_identifier"].as_str())
+                    .unwrap_or("Leave"),
+            );
+            let reason = FreeDayReason::Leave { kind, approved };
             let mut start = parse_date(vacay["start_on"].as_str().unwrap()).unwrap();
             let end = parse_date(vacay["finish_on"].as_str().unwrap()).unwrap();
+            // Half day and hourly leaves only ever span a single day.
+            if start == end {
+                let (half, hours) = parse_leave_part(&vacay);
+                free_days.push(FreeDay {
+                    day: start,
+                    half,
+                    hours,
+                    reason,
+                });
+                continue;
+            }
             while start <= end {
                 free_days.push(FreeDay {
                     day: start,
                     half: HalfDay::WholeDay,
+                    hours: None,
+                    reason: reason.clone(),
                 });
                 start = start.checked_add_days(chrono::Days::new(1)).unwrap();
             }
         }
 
-        let work_days: Vec<chrono::Weekday> = self
-            .config
-            .working_week_days
-            .iter()
-            .map(|s| s.parse::<chrono::Weekday>().unwrap())
-            .collect();
         let mut start = from.clone();
         let end = to.clone();
 
         while start <= end {
-            if !work_days.contains(&start.weekday()) {
+            if !self.config.is_working_day(start.date_naive()) {
                 free_days.push(FreeDay {
                     day: start,
                     half: HalfDay::WholeDay,
+                    hours: None,
+                    reason: FreeDayReason::NonWorkingDay,
                 })
             }
             start = start.checked_add_days(chrono::Days::new(1)).unwrap();
@@ -339,51 +458,186 @@ impl FactorialApi {
         Ok(free_days)
     }
 
-    /// Creates a shift lasting from start to end
+    /// Retrieves the types of leave the employee can request.
     /// # Errors
     /// Returns an Error if:
     /// - the request could not be sent
-    /// - the shift could not be created, possibly because start or end overlap with an existing
-    /// shift or break
-    /// # Panics
-    /// Panics if the period id could not be successfully retrieved.
-    pub fn make_shift(
+    /// - the response body could not be parsed
+    pub fn get_leave_types(&self) -> anyhow::Result<Vec<LeaveType>> {
+        let response = self
+            .client
+            .get(ApiEndpoint::LeaveTypes.url())
+            .query(&[("employee_id", self.config.user_id.as_str())])
+            .send()?;
+        let leave_types: Vec<serde_json::Value> = response.json()?;
+        Ok(leave_types
+            .iter()
+            .filter(|t| t["active"].as_bool().unwrap_or(true))
+            .filter_map(|t| {
+                let name = t["name"].as_str()?.to_string();
+                let identifier = t["identifier"].as_str().unwrap_or(&name);
+                Some(LeaveType {
+                    id: t["id"].as_u64()?,
+                    kind: LeaveKind::from_name(identifier),
+                    name,
+                })
+            })
+            .collect())
+    }
+
+    /// Requests a leave of the given type from the start of `from` until the end of `to`. A half
+    /// day leave can only be requested for a single day.
+    /// # Errors
+    /// Returns an Error if:
+    /// - `to` is before `from`
+    /// - a half day leave spans more than one day
+    /// - the request could not be sent
+    /// - the leave could not be created, possibly because it overlaps with an existing leave
+    pub fn request_leave(
         &self,
-        start: chrono::DateTime<Local>,
-        end: chrono::DateTime<Local>,
+        leave_type: &LeaveType,
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+        half: HalfDay,
+        note: &str,
     ) -> anyhow::Result<()> {
+        if from.date_naive() > to.date_naive() {
+            return Err(anyhow!(
+                "The leave can't end on {} before it starts on {}.",
+                to.format("%Y-%m-%d"),
+                from.format("%Y-%m-%d")
+            ));
+        }
+        if half != HalfDay::WholeDay && from.date_naive() != to.date_naive() {
+            return Err(anyhow!(
+                "A half day leave can only be requested for a single day."
+            ));
+        }
+        let half_day = match half {
+            HalfDay::WholeDay => None,
+            HalfDay::StartOfDay => Some("beginning_of_day".to_string()),
+            HalfDay::EndOfDay => Some("end_of_day".to_string()),
+        };
         let response = self
             .client
-            .post(ApiEndpoint::Shifts.url())
-            .json(&ShiftData::new(
-                start,
-                end,
-                &self.config.location_type,
-                self.get_period_id(start).unwrap(),
-                false,
-            ))
+            .post(ApiEndpoint::Leaves.url())
+            .json(&LeaveData {
+                employee_id: self.config.user_id.parse()?,
+                leave_type_id: leave_type.id,
+                start_on: from.format("%Y-%m-%d").to_string(),
+                finish_on: to.format("%Y-%m-%d").to_string(),
+                half_day,
+                description: note.to_string(),
+            })
             .send()?;
         match response.status() {
-            StatusCode::CREATED => (),
-            StatusCode::CONFLICT => return Err(anyhow!("There already exists a shift at this time. Try again with the --force flag to override existing shifts.")),
-            _ => return Err(anyhow!("Something went wrong. Shift was not created.")),
+            StatusCode::CREATED | StatusCode::OK => Ok(()),
+            StatusCode::CONFLICT | StatusCode::UNPROCESSABLE_ENTITY => Err(anyhow!(
+                "The leave could not be requested. It might overlap with an existing leave or exceed the allowance."
+            )),
+            _ => Err(anyhow!("Something went wrong. Leave was not requested.")),
         }
-        Ok(())
     }
 
-    /// Creates a break lasting from start to end
+    /// Retrieves the yearly allowance per leave type together with the days already taken, the
+    /// days planned and the days remaining.
+    /// # Errors
+    /// Returns an Error if:
+    /// - the request could not be sent
+    /// - the response body could not be parsed
+    pub fn get_leave_balances(&self, year: i32) -> anyhow::Result<Vec<LeaveBalance>> {
+        let response = self
+            .client
+            .get(ApiEndpoint::Allowances.url())
+            .query(&[
+                ("employee_id", self.config.user_id.as_str()),
+                ("year", year.to_string().as_str()),
+            ])
+            .send()?;
+        let allowances: Vec<serde_json::Value> = response.json()?;
+        Ok(allowances
+            .iter()
+            .map(|allowance| {
+                let days = |key: &str| allowance[key].as_f64().unwrap_or(0.0) as f32;
+                let (amount, taken, planned) = (days("amount"), days("consumed"), days("planned"));
+                LeaveBalance {
+                    leave_type: allowance["name"].as_str().unwrap_or("").to_string(),
+                    allowance: amount,
+                    taken,
+                    planned,
+                    remaining: allowance["available"]
+                        .as_f64()
+                        .map(|a| a as f32)
+                        .unwrap_or(amount - taken - planned),
+                }
+            })
+            .collect())
+    }
+
+    /// Creates a shift lasting from start to end and returns the ids of the created shifts. A
+    /// shift spanning midnight is split into one shift per day, each in the period of its day.
     /// # Errors
     /// Returns an Error if:
     /// - the request could not be sent
     /// - the shift could not be created, possibly because start or end overlap with an existing
     /// shift or break
-    /// # Panics
-    /// Panics if the period id could not be successfully retrieved.
+    /// - the attendance period could not be retrieved or is already approved
+    pub fn make_shift(
+        &self,
+        start: chrono::DateTime<Local>,
+        end: chrono::DateTime<Local>,
+    ) -> anyhow::Result<Vec<u64>> {
+        self.make_segments(start, end, false)
+    }
+
+    /// Creates a break lasting from start to end and returns the ids of the created breaks. A
+    /// break spanning midnight is split into one break per day, each in the period of its day.
+    /// # Errors
+    /// Returns an Error if:
+    /// - the request could not be sent
+    /// - the shift could not be created, possibly because start or end overlap with an existing
+    /// shift or break
+    /// - the attendance period could not be retrieved or is already approved
     pub fn make_break(
         &self,
         start: chrono::DateTime<Local>,
         end: chrono::DateTime<Local>,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<Vec<u64>> {
+        self.make_segments(start, end, true)
+    }
+
+    /// Creates a shift or break for every day from start to end. Should one of them fail, the
+    /// ones already created are deleted again.
+    fn make_segments(
+        &self,
+        start: chrono::DateTime<Local>,
+        end: chrono::DateTime<Local>,
+        is_break: bool,
+    ) -> anyhow::Result<Vec<u64>> {
+        let mut created = Vec::new();
+        for (part_start, part_end) in split_at_midnight(start, end) {
+            match self.make_segment(part_start, part_end, is_break) {
+                Ok(id) => created.push(id),
+                Err(err) => {
+                    for id in created {
+                        let _ = self.delete_shift(id);
+                    }
+                    return Err(err);
+                }
+            }
+        }
+        Ok(created)
+    }
+
+    /// Creates a single shift or break that does not span midnight and returns its id.
+    fn make_segment(
+        &self,
+        start: chrono::DateTime<Local>,
+        end: chrono::DateTime<Local>,
+        is_break: bool,
+    ) -> anyhow::Result<u64> {
+        let kind = if is_break { "break" } else { "shift" };
+        let period = self.ensure_editable(start)?;
         let response = self
             .client
             .post(ApiEndpoint::Shifts.url())
@@ -391,40 +645,91 @@ impl FactorialApi {
                 start,
                 end,
                 &self.config.location_type,
-                self.get_period_id(start).unwrap(),
-                true,
+                period.id,
+                is_break,
             ))
             .send()?;
         match response.status() {
             StatusCode::CREATED => (),
             StatusCode::CONFLICT => return Err(anyhow!("There already exists a shift at this time. Try again with the --force flag to override existing shifts.")),
-            _ => return Err(anyhow!("Something went wrong. Break was not created.")),
+            _ => return Err(anyhow!("Something went wrong. The {kind} was not created.")),
         }
+        let created: serde_json::Value = response.json()?;
+        created["id"]
+            .as_u64()
+            .ok_or(anyhow!("Could not get the id of the created {kind}."))
+    }
 
-        Ok(())
+    /// Retrieves the attendance period of the month the given date is in.
+    /// # Errors
+    /// Returns an error if:
+    /// - the request could not be sent
+    /// - the response could not be parsed or contains no period
+    pub fn get_period(&self, date: chrono::DateTime<Local>) -> anyhow::Result<Period> {
+        // The state of the period can change at any time, so it is not kept beyond this run
+        let mut periods: Vec<serde_json::Value> =
+            serde_json::from_value(self.cache.get_or_fetch_in_memory(
+                &period_key(&self.config.user_id, date.year(), date.month()),
+                || {
+                    Ok(self
+                        .client
+                        .get(ApiEndpoint::Periods.url())
+                        .query(&[
+                            ("year", date.year().to_string().as_str()),
+                            ("month", date.month().to_string().as_str()),
+                            ("employee_id", self.config.user_id.as_str()),
+                        ])
+                        .send()?
+                        .json()?)
+                },
+            )?)?;
+        let period = periods
+            .pop()
+            .ok_or(anyhow!("There is no attendance period for this month."))?;
+        parse_period(&period).ok_or(anyhow!("Could not parse the attendance period."))
     }
 
-    /// retrieves the period id for a given date.
+    /// Submits the attendance period for approval by the manager.
     /// # Errors
-    /// Returns an error if the request could not be sent.
-    /// # Panics
-    /// Panics if the response could not be parsed.
-    fn get_period_id(&self, date: chrono::DateTime<Local>) -> anyhow::Result<usize> {
+    /// Returns an error if:
+    /// - the period was already submitted or approved
+    /// - the request could not be sent or was not successful
+    pub fn submit_period(&self, period: &Period) -> anyhow::Result<()> {
+        if period.state != PeriodState::Open {
+            return Err(anyhow!(
+                "The attendance period of {} is already {}.",
+                period.name(),
+                period.state
+            ));
+        }
         let response = self
             .client
-            .get(ApiEndpoint::Periods.url())
-            .query(&[
-                ("year", date.year().to_string().as_str()),
-                ("month", date.month().to_string().as_str()),
-                ("employee_id", self.config.user_id.as_str()),
-            ])
+            .put(ApiEndpoint::Periods.url() + &period.id.to_string())
+            .json(&PeriodData {
+                state: PeriodState::Submitted.to_string(),
+            })
             .send()?;
-        let mut periods: Vec<serde_json::Value> = response.json().unwrap();
-        Ok(periods.pop().unwrap()["id"]
-            .as_u64()
-            .unwrap()
-            .try_into()
-            .unwrap())
+        if !response.status().is_success() {
+            return Err(anyhow!("Something went wrong. Period was not submitted."));
+        }
+        // The state of the period changed
+        self.cache
+            .invalidate(&period_key(&self.config.user_id, period.year, period.month));
+        Ok(())
+    }
+
+    /// Makes sure the attendance period of the given date can still be changed.
+    /// # Errors
+    /// Returns an error if the period could not be retrieved or is already approved.
+    fn ensure_editable(&self, date: chrono::DateTime<Local>) -> anyhow::Result<Period> {
+        let period = self.get_period(date)?;
+        if period.state == PeriodState::Approved {
+            return Err(anyhow!(
+                "The attendance period of {} is already approved and can't be changed anymore.",
+                period.name()
+            ));
+        }
+        Ok(period)
     }
 
     /// simple function to make it more convenient to send a post request with a
@@ -434,6 +739,7 @@ impl FactorialApi {
         endpoint: ApiEndpoint,
         time: DateTime<Local>,
     ) -> anyhow::Result<Response> {
+        self.ensure_editable(time)?;
         let time = time.to_rfc3339();
         let mut params = HashMap::new();
         params.insert("now".to_string(), time);
@@ -448,10 +754,225 @@ impl FactorialApi {
     }
 }
 
+/// Retrieves all versions of the employee's contract, ordered by the date they became effective.
+/// # Errors
+/// Returns an error if the request could not be sent, the employee has no contract or the contract
+/// could not be parsed.
+fn get_contract_versions(
+    client: &blocking::Client,
+    cache: &Cache,
+    user_id: &str,
+) -> anyhow::Result<Vec<ContractVersion>> {
+    let contracts: Vec<serde_json::Value> =
+        serde_json::from_value(cache.get_or_fetch(&format!("contracts/{user_id}"), || {
+            Ok(client
+                .get(ApiEndpoint::Contracts.url())
+                .query(&[("employee_ids[]", user_id)])
+                .send()?
+                .json()?)
+        })?)?;
+    if contracts.is_empty() {
+        return Err(anyhow!("The employee has no contract. Unable to get the amount of working hours. Manually setting the amount in the configuration file can bypass this issue."));
+    }
+    let mut versions = contracts
+        .iter()
+        .map(ContractVersion::from_factorial)
+        .collect::<anyhow::Result<Vec<ContractVersion>>>()?;
+    versions.sort_by_key(|v| v.effective_on);
+    Ok(versions)
+}
+
+/// Reads a shift or break as returned by the shifts endpoint. A clock out before the clock in
+/// means the shift ended on the next day and a clock out at 23:59:59 means it ended at midnight.
+fn parse_shift(shift: &serde_json::Value) -> Option<Shift> {
+    let parse_time = |time: &serde_json::Value| {
+        let time = time.as_str()?;
+        chrono::NaiveTime::parse_from_str(time, "%H:%M:%S")
+            .or(chrono::NaiveTime::parse_from_str(time, "%H:%M"))
+            .ok()
+    };
+    let date = chrono::NaiveDate::parse_from_str(shift["date"].as_str()?, "%Y-%m-%d").ok()?;
+    let start = date
+        .and_time(parse_time(&shift["clock_in"])?)
+        .and_local_timezone(Local)
+        .earliest()?;
+    let end = parse_time(&shift["clock_out"]).and_then(|clock_out| {
+        let mut end = date.and_time(clock_out);
+        if clock_out == chrono::NaiveTime::from_hms_opt(23, 59, 59).unwrap() {
+            end += chrono::Duration::seconds(1);
+        } else if end < start.naive_local() {
+            end += chrono::Duration::days(1);
+        }
+        end.and_local_timezone(Local).earliest()
+    });
+    Some(Shift {
+        id: shift["id"].as_u64()?,
+        start,
+        end,
+        is_break: !shift["workable"].as_bool().unwrap_or(true),
+    })
+}
+
+/// Get the key the attendance period of a month is cached with.
+fn period_key(user_id: &str, year: i32, month: u32) -> String {
+    format!("periods/{user_id}/{year}-{month}")
+}
+
+/// Parses an attendance period as returned by Factorial.
+fn parse_period(period: &serde_json::Value) -> Option<Period> {
+    let state = match period["state"].as_str().unwrap_or("") {
+        "approved" => PeriodState::Approved,
+        "submitted" | "pending_approval" => PeriodState::Submitted,
+        _ => PeriodState::Open,
+    };
+    let minutes = |key: &str| period[key].as_i64().map(chrono::Duration::minutes);
+    Some(Period {
+        id: period["id"].as_u64()?.try_into().ok()?,
+        year: period["year"].as_i64()?.try_into().ok()?,
+        month: period["month"].as_u64()?.try_into().ok()?,
+        state,
+        worked: minutes("worked_minutes"),
+        expected: minutes("estimated_minutes"),
+    })
+}
+
+/// Reads the part of the day a holiday or leave covers from its `half_day` field.
+fn parse_half_day(half_day: &serde_json::Value) -> HalfDay {
+    match half_day.as_str() {
+        None => HalfDay::WholeDay,
+        Some("end_of_day") => HalfDay::EndOfDay,
+        Some(_) => HalfDay::StartOfDay,
+    }
+}
+
+/// Reads which part of the day a single day leave covers. Leaves measured in hours, like doctor's
+/// appointments, carry their length in `hours_amount_in_cents` and are placed at the start or the
+/// end of the day depending on whether they start before noon.
+fn parse_leave_part(leave: &serde_json::Value) -> (HalfDay, Option<chrono::Duration>) {
+    let hours_in_cents = match leave["hours_amount_in_cents"].as_f64() {
+        Some(hours) if hours > 0.0 => hours,
+        _ => return (parse_half_day(&leave["half_day"]), None),
+    };
+    let hours = chrono::Duration::seconds((hours_in_cents / 100.0 * 60.0 * 60.0).round() as i64);
+    let starts_in_the_morning = leave["start_time"]
+        .as_str()
+        .and_then(|t| chrono::NaiveTime::parse_from_str(t, "%H:%M").ok())
+        .map(|t| t < chrono::NaiveTime::from_hms_opt(12, 0, 0).unwrap())
+        .unwrap_or(false);
+    if starts_in_the_morning {
+        (HalfDay::StartOfDay, Some(hours))
+    } else {
+        (HalfDay::EndOfDay, Some(hours))
+    }
+}
+
+/// A type of leave an employee can request, e.g. vacation or sick leave.
+#[derive(Debug, Clone)]
+pub struct LeaveType {
+    /// The Id Factorial uses to identify the leave type
+    pub id: u64,
+    /// The name of the leave type as shown in Factorial
+    pub name: String,
+    /// The kind of leave
+    pub kind: LeaveKind,
+}
+
+impl LeaveType {
+    /// Find the leave type with the given name as shown in Factorial or, failing that, the first
+    /// one of the kind the name describes, e.g. vacation or sick-leave.
+    pub fn find<'a>(leave_types: &'a [LeaveType], name: &str) -> Option<&'a LeaveType> {
+        let wanted = LeaveKind::from_name(&name.replace('-', " "));
+        leave_types
+            .iter()
+            .find(|t| t.name.to_lowercase() == name.to_lowercase())
+            .or(leave_types.iter().find(|t| t.kind == wanted))
+    }
+}
+
+/// The balance of a yearly leave allowance in days.
+#[derive(Debug, Clone)]
+pub struct LeaveBalance {
+    /// The name of the leave type the allowance is for
+    pub leave_type: String,
+    /// The amount of days available for the whole year
+    pub allowance: f32,
+    /// The amount of days already taken
+    pub taken: f32,
+    /// The amount of days requested but not yet taken
+    pub planned: f32,
+    /// The amount of days that can still be requested
+    pub remaining: f32,
+}
+
+/// An attendance period, i.e. the month of shifts that is approved by the manager as a whole.
+#[derive(Debug, Clone)]
+pub struct Period {
+    /// The Id Factorial uses to identify the period
+    pub id: usize,
+    /// The year of the period
+    pub year: i32,
+    /// The month of the period
+    pub month: u32,
+    /// Whether the period is still open, submitted or already approved
+    pub state: PeriodState,
+    /// The time worked in the period as calculated by Factorial
+    pub worked: Option<chrono::Duration>,
+    /// The time expected to be worked in the period as calculated by Factorial
+    pub expected: Option<chrono::Duration>,
+}
+impl Period {
+    /// Get the name of the month of the period, e.g. May 2024.
+    pub fn name(&self) -> String {
+        match chrono::NaiveDate::from_ymd_opt(self.year, self.month, 1) {
+            Some(first) => first.format("%B %Y").to_string(),
+            None => format!("{}-{:02}", self.year, self.month),
+        }
+    }
+}
+
+/// The state of an attendance period.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PeriodState {
+    /// Shifts can still be changed
+    Open,
+    /// Waiting for the approval of the manager
+    Submitted,
+    /// Approved by the manager, shifts can't be changed anymore
+    Approved,
+}
+impl std::fmt::Display for PeriodState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Open => write!(f, "open"),
+            Self::Submitted => write!(f, "submitted"),
+            Self::Approved => write!(f, "approved"),
+        }
+    }
+}
+
+/// All the data required to submit a period that can be serialized to json and sent as a request
+/// body.
+#[derive(Serialize)]
+struct PeriodData {
+    state: String,
+}
+
+/// All the data required to request a leave that can be serialized to json and sent as a request
+/// body.
+#[derive(Serialize)]
+struct LeaveData {
+    employee_id: u64,
+    leave_type_id: u64,
+    start_on: String,
+    finish_on: String,
+    half_day: Option<String>,
+    description: String,
+}
+
 /// All the data required to create a shift or break that can be serialized to json and sent as a
 /// request body.
 #[derive(Serialize)]
-struct ShiftData {
+pub struct ShiftData {
     clock_in: String,
     clock_out: String,
     date: String,
@@ -465,16 +986,24 @@ struct ShiftData {
 }
 
 impl ShiftData {
-    fn new(
+    /// Get the data to book the part of a shift or break from `start` to `end`, which has to lie
+    /// on a single day.
+    pub fn new(
         start: chrono::DateTime<Local>,
         end: chrono::DateTime<Local>,
         location_type: &str,
         period_id: usize,
         is_break: bool,
     ) -> Self {
+        // A part of a night shift that ends at midnight is booked until the end of its day
+        let clock_out = if end.date_naive() > start.date_naive() {
+            "23:59:59".to_string()
+        } else {
+            end.format("%H:%M").to_string()
+        };
         ShiftData {
             clock_in: start.format("%H:%M").to_string(),
-            clock_out: end.format("%H:%M").to_string(),
+            clock_out,
             date: start.format("%Y-%m-%d").to_string(),
             day: start.format("%d").to_string().parse::<usize>().unwrap(),
             location_type: location_type.to_string(),
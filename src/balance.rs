@@ -0,0 +1,103 @@
+use std::ops::{Add, AddAssign};
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate};
+
+use crate::{
+    break_policy::BreakPolicy,
+    time::{remaining_work, workday_of, FreeDay, Shift},
+};
+
+/// The amount of time that was expected to be worked and the amount of time that was actually
+/// worked over a period, e.g. a day or a week.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Balance {
+    /// The time that should have been worked
+    pub expected: Duration,
+    /// The time that was worked
+    pub worked: Duration,
+}
+
+impl Balance {
+    /// Get the amount of overtime, negative if less time was worked than expected.
+    pub fn surplus(&self) -> Duration {
+        self.worked - self.expected
+    }
+}
+impl Add for Balance {
+    type Output = Balance;
+
+    fn add(self, other: Balance) -> Balance {
+        Balance {
+            expected: self.expected + other.expected,
+            worked: self.worked + other.worked,
+        }
+    }
+}
+impl AddAssign for Balance {
+    fn add_assign(&mut self, other: Balance) {
+        *self = *self + other;
+    }
+}
+
+/// Get the balance of every day from `from` until `to`. The expected time for a day is given by
+/// `target` and reduced by holidays, leaves and non-working days in `free_days`. Pending leaves do
+/// not reduce the expected time. The worked time is the sum of all closed shifts of that workday,
+/// breaks excluded, so the part of a night shift after midnight counts for the day it started on.
+pub fn daily_balances(
+    from: DateTime<Local>,
+    to: DateTime<Local>,
+    target: impl Fn(NaiveDate) -> Duration,
+    free_days: &[FreeDay],
+    shifts: &[Shift],
+    policy: &dyn BreakPolicy,
+) -> Vec<(NaiveDate, Balance)> {
+    let free_days: Vec<FreeDay> = free_days
+        .iter()
+        .filter(|d| !d.is_pending())
+        .cloned()
+        .collect();
+    let mut balances = Vec::new();
+    let mut day = from;
+    while day.date_naive() <= to.date_naive() {
+        let date = day.date_naive();
+        let expected = match remaining_work(day, target(date), &free_days, policy) {
+            Some((_, duration)) => duration,
+            None => Duration::zero(),
+        };
+        let worked = shifts
+            .iter()
+            .filter(|s| !s.is_break && workday_of(s, shifts) == date)
+            .fold(Duration::zero(), |worked, s| worked + s.duration());
+        balances.push((date, Balance { expected, worked }));
+        day = day.checked_add_days(chrono::Days::new(1)).unwrap();
+    }
+    balances
+}
+
+/// Sum up consecutive daily balances that share the same key, e.g. the same week or month.
+pub fn group_balances<K: PartialEq>(
+    balances: &[(NaiveDate, Balance)],
+    key: impl Fn(NaiveDate) -> K,
+) -> Vec<(K, Balance)> {
+    let mut groups: Vec<(K, Balance)> = Vec::new();
+    for (day, balance) in balances {
+        let day_key = key(*day);
+        match groups.last_mut() {
+            Some((group_key, group_balance)) if *group_key == day_key => *group_balance += *balance,
+            _ => groups.push((day_key, *balance)),
+        }
+    }
+    groups
+}
+
+/// Get the balance of every week, weeks are identified by their ISO year and week number.
+pub fn weekly_balances(balances: &[(NaiveDate, Balance)]) -> Vec<((i32, u32), Balance)> {
+    group_balances(balances, |day| {
+        (day.iso_week().year(), day.iso_week().week())
+    })
+}
+
+/// Get the balance of every month, months are identified by their year and month number.
+pub fn monthly_balances(balances: &[(NaiveDate, Balance)]) -> Vec<((i32, u32), Balance)> {
+    group_balances(balances, |day| (day.year(), day.month()))
+}
@@ -0,0 +1,138 @@
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+/// Rules for how long the breaks during a workday have to be.
+pub trait BreakPolicy {
+    /// Get the mandatory duration of all breaks combined for the given duration of work.
+    fn break_duration(&self, work_duration: Duration) -> Duration;
+    /// Get the longest duration that may be worked without taking a break, `None` if breaks are
+    /// never required.
+    fn max_work_without_break(&self) -> Option<Duration>;
+}
+
+/// An amount of break time that is required once the work reaches a certain duration.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct BreakThreshold {
+    /// The amount of hours of work from which on the break is required
+    pub after_hours: f32,
+    /// The total amount of break in minutes that is required
+    pub minutes: u32,
+}
+
+impl BreakThreshold {
+    fn new(after_hours: f32, minutes: u32) -> Self {
+        BreakThreshold {
+            after_hours,
+            minutes,
+        }
+    }
+}
+
+/// Get the break duration of the highest threshold the work duration reaches.
+fn break_by_thresholds(thresholds: &[BreakThreshold], work_duration: Duration) -> Duration {
+    thresholds
+        .iter()
+        .filter(|t| work_duration.num_seconds() as f32 >= t.after_hours * 60.0 * 60.0)
+        .map(|t| Duration::minutes(t.minutes.into()))
+        .max()
+        .unwrap_or(Duration::zero())
+}
+
+/// Get the work duration of the lowest threshold.
+fn max_work_by_thresholds(thresholds: &[BreakThreshold]) -> Option<Duration> {
+    thresholds
+        .iter()
+        .filter(|t| t.minutes > 0)
+        .map(|t| Duration::seconds((t.after_hours * 60.0 * 60.0) as i64))
+        .min()
+}
+
+/// Breaks as required by german law (ArbZG §4): 30 minutes from 6 hours of work on, 45 minutes
+/// from 9 hours on.
+pub struct German;
+impl BreakPolicy for German {
+    fn break_duration(&self, work_duration: Duration) -> Duration {
+        break_by_thresholds(
+            &[BreakThreshold::new(6.0, 30), BreakThreshold::new(9.0, 45)],
+            work_duration,
+        )
+    }
+    fn max_work_without_break(&self) -> Option<Duration> {
+        Some(Duration::hours(6))
+    }
+}
+
+/// Breaks as required by austrian law (AZG §11): 30 minutes from 6 hours of work on.
+pub struct Austrian;
+impl BreakPolicy for Austrian {
+    fn break_duration(&self, work_duration: Duration) -> Duration {
+        break_by_thresholds(&[BreakThreshold::new(6.0, 30)], work_duration)
+    }
+    fn max_work_without_break(&self) -> Option<Duration> {
+        Some(Duration::hours(6))
+    }
+}
+
+/// Breaks as required by spanish law (Estatuto de los Trabajadores art. 34.4): 15 minutes from 6
+/// hours of work on.
+pub struct Spanish;
+impl BreakPolicy for Spanish {
+    fn break_duration(&self, work_duration: Duration) -> Duration {
+        break_by_thresholds(&[BreakThreshold::new(6.0, 15)], work_duration)
+    }
+    fn max_work_without_break(&self) -> Option<Duration> {
+        Some(Duration::hours(6))
+    }
+}
+
+/// Breaks as required by swiss law (ArG art. 15): 15 minutes from 5.5 hours of work on, 30
+/// minutes from 7 hours on and an hour from 9 hours on.
+pub struct Swiss;
+impl BreakPolicy for Swiss {
+    fn break_duration(&self, work_duration: Duration) -> Duration {
+        break_by_thresholds(
+            &[
+                BreakThreshold::new(5.5, 15),
+                BreakThreshold::new(7.0, 30),
+                BreakThreshold::new(9.0, 60),
+            ],
+            work_duration,
+        )
+    }
+    fn max_work_without_break(&self) -> Option<Duration> {
+        Some(Duration::minutes(330))
+    }
+}
+
+/// The break policy as chosen in the configuration, either the name of a country or a custom
+/// table of thresholds.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum BreakPolicyConfig {
+    Germany,
+    Austria,
+    Spain,
+    Switzerland,
+    Custom(Vec<BreakThreshold>),
+}
+
+impl BreakPolicy for BreakPolicyConfig {
+    fn break_duration(&self, work_duration: Duration) -> Duration {
+        match self {
+            Self::Germany => German.break_duration(work_duration),
+            Self::Austria => Austrian.break_duration(work_duration),
+            Self::Spain => Spanish.break_duration(work_duration),
+            Self::Switzerland => Swiss.break_duration(work_duration),
+            Self::Custom(thresholds) => break_by_thresholds(thresholds, work_duration),
+        }
+    }
+    fn max_work_without_break(&self) -> Option<Duration> {
+        match self {
+            Self::Germany => German.max_work_without_break(),
+            Self::Austria => Austrian.max_work_without_break(),
+            Self::Spain => Spanish.max_work_without_break(),
+            Self::Switzerland => Swiss.max_work_without_break(),
+            Self::Custom(thresholds) => max_work_by_thresholds(thresholds),
+        }
+    }
+}
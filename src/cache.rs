@@ -0,0 +1,172 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::Write,
+    path::PathBuf,
+    sync::Mutex,
+};
+
+use anyhow::anyhow;
+use chrono::{DateTime, Duration, Local};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Configuration;
+
+/// A response of the Factorial API together with the time it was retrieved.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Entry {
+    fetched_at: DateTime<Local>,
+    value: serde_json::Value,
+    /// Whether the entry is only kept in memory and never persisted
+    #[serde(skip)]
+    in_memory: bool,
+}
+
+/// Keeps responses of the Factorial API that rarely change, e.g. attendance periods and company
+/// holidays, so they only have to be retrieved once. Entries can optionally be persisted to a file
+/// and are retrieved again once they are older than the time to live. Entries that must not be
+/// outdated are only kept in memory. The cache can be shared between threads.
+#[derive(Debug)]
+pub struct Cache {
+    entries: Mutex<HashMap<String, Entry>>,
+    /// The file the entries are persisted to, None if they are only kept in memory
+    path: Option<PathBuf>,
+    /// How long entries stay valid, None if they stay valid as long as the cache exists
+    ttl: Option<Duration>,
+}
+
+impl Cache {
+    /// Get a cache that only keeps entries in memory.
+    pub fn in_memory() -> Cache {
+        Cache {
+            entries: Mutex::new(HashMap::new()),
+            path: None,
+            ttl: None,
+        }
+    }
+
+    /// Get a cache that persists its entries to the file at `path` and keeps them for `ttl`.
+    /// Entries already in the file are loaded, an unreadable file is ignored.
+    pub fn persisted(path: PathBuf, ttl: Duration) -> Cache {
+        let entries: HashMap<String, Entry> = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        let cache = Cache {
+            entries: Mutex::new(entries),
+            path: Some(path),
+            ttl: Some(ttl),
+        };
+        cache
+            .entries
+            .lock()
+            .unwrap()
+            .retain(|_, e| cache.is_fresh(e));
+        cache
+    }
+
+    /// Get the cache as configured by `cache_ttl`. Entries are only kept in memory if it is 0 or
+    /// the cache file can not be created.
+    pub fn from_config(config: &Configuration) -> Cache {
+        if config.cache_ttl == 0 {
+            return Cache::in_memory();
+        }
+        match Cache::get_cache_path() {
+            Ok(path) => Cache::persisted(path, Duration::minutes(config.cache_ttl.into())),
+            Err(_) => Cache::in_memory(),
+        }
+    }
+
+    /// Get the cached value for `key` or retrieve it with `fetch` and cache it if there is no
+    /// fresh value.
+    /// # Errors
+    /// Returns the error of `fetch`.
+    pub fn get_or_fetch(
+        &self,
+        key: &str,
+        fetch: impl FnOnce() -> anyhow::Result<serde_json::Value>,
+    ) -> anyhow::Result<serde_json::Value> {
+        self.get_or_fetch_entry(key, fetch, false)
+    }
+
+    /// Like `get_or_fetch`, but the value is only kept in memory and never persisted, e.g. for
+    /// values that can change at any time and must not be outdated in the next run.
+    /// # Errors
+    /// Returns the error of `fetch`.
+    pub fn get_or_fetch_in_memory(
+        &self,
+        key: &str,
+        fetch: impl FnOnce() -> anyhow::Result<serde_json::Value>,
+    ) -> anyhow::Result<serde_json::Value> {
+        self.get_or_fetch_entry(key, fetch, true)
+    }
+
+    fn get_or_fetch_entry(
+        &self,
+        key: &str,
+        fetch: impl FnOnce() -> anyhow::Result<serde_json::Value>,
+        in_memory: bool,
+    ) -> anyhow::Result<serde_json::Value> {
+        if let Some(entry) = self.entries.lock().unwrap().get(key) {
+            // Persisted entries of earlier runs are not used for values that are kept in memory
+            if self.is_fresh(entry) && entry.in_memory == in_memory {
+                return Ok(entry.value.clone());
+            }
+        }
+        // Don't hold the lock while waiting for the response, so other threads can use the cache
+        let value = fetch()?;
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(
+            key.to_string(),
+            Entry {
+                fetched_at: Local::now(),
+                value: value.clone(),
+                in_memory,
+            },
+        );
+        // Failing to persist the cache only means the value has to be retrieved again next time
+        let _ = self.save(&entries);
+        Ok(value)
+    }
+
+    /// Remove the value for `key`, so it is retrieved again the next time it is needed.
+    pub fn invalidate(&self, key: &str) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.remove(key).is_some() {
+            let _ = self.save(&entries);
+        }
+    }
+
+    fn is_fresh(&self, entry: &Entry) -> bool {
+        match self.ttl {
+            Some(ttl) => Local::now() - entry.fetched_at < ttl,
+            None => true,
+        }
+    }
+
+    /// Writes the entries to the cache file if the cache is persisted.
+    fn save(&self, entries: &HashMap<String, Entry>) -> anyhow::Result<()> {
+        if let Some(path) = &self.path {
+            let persisted: HashMap<&String, &Entry> =
+                entries.iter().filter(|(_, e)| !e.in_memory).collect();
+            let mut file = File::create(path)?;
+            file.write_all(serde_json::to_string(&persisted)?.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Retrieves the path of the cache file. Missing parent directories are created.
+    ///
+    /// # Errors
+    /// Returns an error if the home directory can not be determined or the cache directory can
+    /// not be created.
+    fn get_cache_path() -> anyhow::Result<PathBuf> {
+        let cache_dir = match ProjectDirs::from("", "", "Tracktorial") {
+            Some(dirs) => dirs.cache_dir().to_owned(),
+            None => return Err(anyhow!("Could not determine the home directory.")),
+        };
+        fs::create_dir_all(&cache_dir)?;
+        Ok(cache_dir.join("cache.json"))
+    }
+}
@@ -1,7 +1,22 @@
-use crate::{api::FactorialApi, config::Configuration, login::Credential, time};
-use chrono::{DateTime, Local, NaiveTime};
+use crate::{
+    api::{FactorialApi, LeaveType},
+    balance, compliance,
+    config::{Configuration, PendingLeaves},
+    login::Credential,
+    time,
+};
+use anyhow::anyhow;
+use chrono::{DateTime, Datelike, Local, NaiveTime};
 use clap::{Args, Parser, Subcommand};
-use std::{process::exit, u16};
+use std::{
+    io::IsTerminal,
+    process::exit,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    u16,
+};
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
@@ -16,6 +31,12 @@ enum Commands {
     BreakStart(BreakStart),
     BreakEnd(BreakEnd),
     Auto(Auto),
+    FreeDays(FreeDays),
+    Leave(Leave),
+    Balance(Balance),
+    Audit(Audit),
+    Period(Period),
+    Status(Status),
     Config(Config),
 }
 
@@ -25,25 +46,41 @@ struct ShiftStart {
     /// Start shift now
     #[arg(short, long, required_unless_present("time"))]
     now: bool,
-    /// Start shift at the specified time. Time should have a format of HH:MM:SS or HH:MM
-    #[arg(short, long, default_value = "", conflicts_with("now"))]
+    /// Start shift at the specified time. Time should have a format of HH:MM:SS
+    /// or HH:MM, 5:30pm, "yesterday 17:45" or relative to now like -10m or now-5m.
+    #[arg(
+        short,
+        long,
+        default_value = "",
+        conflicts_with("now"),
+        allow_hyphen_values = true
+    )]
     time: String,
+    /// The day of <TIME>, defaults to today. Date should be formatted like DD.MM.YYYY or
+    /// YYYY-MM-DD, "today" and "yesterday" work as well.
+    #[arg(long, requires("time"))]
+    date: Option<String>,
     /// Start a shift either now or at <TIME> and end it after <DURATION> or at <END>. Duration
-    /// Should have a format of <hours>h<minutes>m<seconds>, <hours>h<minutes>m or <hours>h.
+    /// Should have a format like <hours>h<minutes>m<seconds>s, 7.5h, 90m or PT7H30M.
     #[arg(short, long, default_value = "")]
     duration: String,
-    /// The started shift should end at <END> (HH:MM:SS or HH:MM)
+    /// The started shift should end at <END>. Time should have a format of HH:MM:SS or HH:MM,
+    /// 5:30pm, "yesterday 17:45" or relative to now like +30m or now+30m.
     #[arg(
         short,
         long,
         default_value = "",
         requires("time"),
-        conflicts_with("duration")
+        conflicts_with("duration"),
+        allow_hyphen_values = true
     )]
     end: String,
     /// Override existing shifts
     #[arg(short, long)]
     force: bool,
+    /// Refuse to book the shift if it violates working-time rules instead of printing warnings
+    #[arg(long)]
+    strict: bool,
 }
 impl ShiftStart {
     fn run(&self, api: FactorialApi) {
@@ -51,14 +88,28 @@ impl ShiftStart {
         if self.now == true {
             start = Local::now();
         } else {
-            start = match time::parse_date_time(&self.time) {
-                Ok(t) => t,
-                Err(_) => {
-                    eprintln!("{}", TIME_ERR_MSG);
-                    exit(0)
-                }
-            }
+            start = parse_time_on(&self.time, self.date.as_deref());
+        }
+        let mut end: Option<DateTime<Local>> = None;
+        if self.duration.len() != 0 {
+            let duration = parse_duration_arg(&self.duration);
+            end = Some(start + duration);
+        } else if self.end.len() != 0 {
+            let end_time = parse_time_on(&self.end, self.date.as_deref());
+            end = Some(time::end_after(start, end_time).unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                exit(0)
+            }))
         }
+        // A shift without an end is only checked for work on free days
+        let shift = time::Shift {
+            id: 0,
+            start,
+            end,
+            is_break: false,
+        };
+        check_compliance(&api, &[shift], self.force, self.strict);
+        close_forgotten_shifts(&api, start);
         if self.force == true {
             match api.delete_all_shifts(start) {
                 Ok(_) => {}
@@ -68,40 +119,15 @@ impl ShiftStart {
                 }
             };
         }
-        match api.shift_start(start) {
-            Err(e) => {
-                eprintln!("{}", e.to_string());
-                exit(0)
-            }
-            Ok(_) => {}
-        }
-        if self.duration.len() != 0 || self.end.len() != 0 {
-            let end: DateTime<Local>;
-            if self.duration.len() != 0 {
-                let duration = match time::parse_duration(&self.duration) {
-                    Ok(d) => d,
-                    Err(_) => {
-                        eprintln!("{}", DUR_ERR_MSG);
-                        exit(0)
-                    }
-                };
-                end = start + duration;
-            } else {
-                end = match time::parse_date_time(&self.end) {
-                    Ok(t) => t,
-                    Err(_) => {
-                        eprintln!("{}", TIME_ERR_MSG);
-                        exit(0)
-                    }
-                }
-            }
-            match api.shift_end(end) {
-                Ok(_) => {}
-                Err(e) => {
-                    eprintln!("{}", e.to_string());
-                    exit(0)
-                }
-            };
+        // A shift with an end is created as a whole, so it is split at midnight if it ends on
+        // the next day
+        let result = match end {
+            Some(end) => api.make_shift(start, end).map(|_| ()),
+            None => api.shift_start(start),
+        };
+        if let Err(e) = result {
+            eprintln!("{}", e.to_string());
+            exit(0)
         }
     }
 }
@@ -111,9 +137,23 @@ struct ShiftEnd {
     /// End shift now
     #[arg(short, long, required_unless_present("time"))]
     now: bool,
-    /// End shift at the specified time. Time should have a format of HH:MM:SS or HH:MM.
-    #[arg(short, long, default_value = "", conflicts_with("now"))]
+    /// End shift at the specified time. Time should have a format of HH:MM:SS
+    /// or HH:MM, 5:30pm, "yesterday 17:45" or relative to now like -10m or now-5m.
+    #[arg(
+        short,
+        long,
+        default_value = "",
+        conflicts_with("now"),
+        allow_hyphen_values = true
+    )]
     time: String,
+    /// The day of <TIME>, defaults to today. Date should be formatted like DD.MM.YYYY or
+    /// YYYY-MM-DD, "today" and "yesterday" work as well.
+    #[arg(long, requires("time"))]
+    date: Option<String>,
+    /// Refuse to end the shift if it violates working-time rules instead of printing warnings
+    #[arg(long)]
+    strict: bool,
 }
 impl ShiftEnd {
     fn run(&self, api: FactorialApi) {
@@ -121,14 +161,9 @@ impl ShiftEnd {
         if self.now == true {
             end = Local::now();
         } else {
-            end = match time::parse_date_time(&self.time) {
-                Ok(t) => t,
-                Err(_) => {
-                    eprintln!("{}", TIME_ERR_MSG);
-                    exit(0)
-                }
-            }
+            end = parse_time_on(&self.time, self.date.as_deref());
         }
+        check_compliance(&api, &open_shifts_closed_at(&api, end), false, self.strict);
         api.shift_end(end).unwrap_or_else(|err| {
             eprintln!("{}", err.to_string());
             exit(0)
@@ -141,22 +176,38 @@ struct BreakStart {
     /// Start a break now
     #[arg(short, long, required_unless_present("time"))]
     now: bool,
-    /// Start a break at the specified time. Time should have a format of HH:MM:SS or HH:MM
-    #[arg(short, long, default_value = "", conflicts_with("now"))]
+    /// Start a break at the specified time. Time should have a format of HH:MM:SS
+    /// or HH:MM, 5:30pm, "yesterday 17:45" or relative to now like -10m or now-5m.
+    #[arg(
+        short,
+        long,
+        default_value = "",
+        conflicts_with("now"),
+        allow_hyphen_values = true
+    )]
     time: String,
-    /// Start a break and end it after the specified duration. Duration should have a format of
-    /// <hours>h<minutes>m<seconds>s, <hours>h<minutes>m or <hours>h.
+    /// The day of <TIME>, defaults to today. Date should be formatted like DD.MM.YYYY or
+    /// YYYY-MM-DD, "today" and "yesterday" work as well.
+    #[arg(long, requires("time"))]
+    date: Option<String>,
+    /// Start a break and end it after the specified duration. Duration should have a format like
+    /// <hours>h<minutes>m<seconds>s, 7.5h, 90m or PT7H30M.
     #[arg(short, long, default_value = "")]
     duration: String,
-    /// The started shift should end at <END> (HH:MM:SS or HH:MM)
+    /// The started break should end at <END>. Time should have a format of HH:MM:SS or HH:MM,
+    /// 5:30pm, "yesterday 17:45" or relative to now like +30m or now+30m.
     #[arg(
         short,
         long,
         default_value = "",
         requires("time"),
-        conflicts_with("duration")
+        conflicts_with("duration"),
+        allow_hyphen_values = true
     )]
     end: String,
+    /// Refuse to start the break if it violates working-time rules instead of printing warnings
+    #[arg(long)]
+    strict: bool,
 }
 impl BreakStart {
     fn run(&self, api: FactorialApi) {
@@ -164,38 +215,29 @@ impl BreakStart {
         if self.now == true {
             start = Local::now();
         } else {
-            start = match time::parse_date_time(&self.time) {
-                Ok(t) => t,
-                Err(_) => {
-                    eprintln!("{}", TIME_ERR_MSG);
-                    exit(0)
-                }
-            }
+            start = parse_time_on(&self.time, self.date.as_deref());
+        }
+        let mut end: Option<DateTime<Local>> = None;
+        if self.duration.len() != 0 {
+            let duration = parse_duration_arg(&self.duration);
+            end = Some(start + duration);
+        } else if self.end.len() != 0 {
+            end = Some(parse_time_on(&self.end, self.date.as_deref()))
         }
-        if api.break_start(start).is_err() {
-            eprintln!("Something went wrong. There is either already an ongoing break or there is no open shift to take a break from.");
+        // The work before the break ends when it starts
+        let mut planned = open_shifts_closed_at(&api, start);
+        planned.push(time::Shift {
+            id: 0,
+            start,
+            end,
+            is_break: true,
+        });
+        check_compliance(&api, &planned, false, self.strict);
+        if let Err(err) = api.break_start(start) {
+            eprintln!("{}", err);
             exit(0)
         }
-        if self.duration.len() != 0 || self.end.len() != 0 {
-            let end: DateTime<Local>;
-            if self.duration.len() != 0 {
-                let duration = match time::parse_duration(&self.duration) {
-                    Ok(d) => d,
-                    Err(_) => {
-                        eprintln!("{}", DUR_ERR_MSG);
-                        exit(0)
-                    }
-                };
-                end = start + duration;
-            } else {
-                end = match time::parse_date_time(&self.end) {
-                    Ok(t) => t,
-                    Err(_) => {
-                        eprintln!("{}", TIME_ERR_MSG);
-                        exit(0)
-                    }
-                }
-            }
+        if let Some(end) = end {
             api.break_end(end).expect(
                 "This should never happen. Things should have went to shit way before this.",
             );
@@ -208,9 +250,23 @@ struct BreakEnd {
     /// End break now
     #[arg(short, long, required_unless_present("time"))]
     now: bool,
-    /// End break at the specified time. Time should have a format of HH:MM:SS or HH:MM.
-    #[arg(short, long, default_value = "", conflicts_with("now"))]
+    /// End break at the specified time. Time should have a format of HH:MM:SS
+    /// or HH:MM, 5:30pm, "yesterday 17:45" or relative to now like -10m or now-5m.
+    #[arg(
+        short,
+        long,
+        default_value = "",
+        conflicts_with("now"),
+        allow_hyphen_values = true
+    )]
     time: String,
+    /// The day of <TIME>, defaults to today. Date should be formatted like DD.MM.YYYY or
+    /// YYYY-MM-DD, "today" and "yesterday" work as well.
+    #[arg(long, requires("time"))]
+    date: Option<String>,
+    /// Refuse to end the break if it violates working-time rules instead of printing warnings
+    #[arg(long)]
+    strict: bool,
 }
 impl BreakEnd {
     fn run(&self, api: FactorialApi) {
@@ -218,16 +274,18 @@ impl BreakEnd {
         if self.now == true {
             end = Local::now();
         } else {
-            end = match time::parse_date_time(&self.time) {
-                Ok(t) => t,
-                Err(_) => {
-                    eprintln!("{}", TIME_ERR_MSG);
-                    exit(0)
-                }
-            }
+            end = parse_time_on(&self.time, self.date.as_deref());
         }
-        if api.break_end(end).is_err() {
-            eprintln!("There is no ongoing break.");
+        // Work continues after the break
+        let shift = time::Shift {
+            id: 0,
+            start: end,
+            end: None,
+            is_break: false,
+        };
+        check_compliance(&api, &[shift], false, self.strict);
+        if let Err(err) = api.break_end(end) {
+            eprintln!("{}", err);
             exit(0)
         }
     }
@@ -237,13 +295,19 @@ impl BreakEnd {
 struct Auto {
     /// Start to work now, take a break, go home. Uses the default duration if
     /// <DURATION> or <END> is not given.
-    #[arg(short, long, conflicts_with("start"), required_unless_present("start"))]
+    #[arg(
+        short,
+        long,
+        conflicts_with("start"),
+        required_unless_present_any(["start", "from", "complete_today", "complete_date"])
+    )]
     now: bool,
-    /// Start a shift now if <NOW> is set or at <START> with the given duration, also takes an appropriately sized break. Duration should have a format of <hours>h<minutes>m<seconds>s, <hours>h<minutes>m or <hours>h.
+    /// Start a shift now if <NOW> is set or at <START> with the given duration, also takes an appropriately sized break. Duration should have a format like <hours>h<minutes>m<seconds>s, 7.5h, 90m or PT7H30M.
     #[arg(short, long, conflicts_with("end"), default_value = "")]
     duration: String,
     /// Start a shift at <START> until <END> or with a given <DURATION>. If neither is present
-    /// the default duration is used. Time should be formated like HH:MM:SS or HH:MM.
+    /// the default duration is used. Time should be formated like HH:MM:SS or HH:MM. Days with a
+    /// schedule in the configuration use its start time if neither <START> nor <NOW> is given.
     #[arg(long, default_value = "")]
     start: String,
     /// If <START> is given, start a shift lasting until <STOP>. mutually exclusive with
@@ -259,15 +323,35 @@ struct Auto {
     /// Override existing shifts.
     #[arg(short, long)]
     force: bool,
+    /// Only book days that don't have any shifts or breaks yet and skip all others
+    #[arg(long, conflicts_with("force"))]
+    fill_missing: bool,
     /// Add a random offset to all time related values
     #[arg(short, long)]
     randomize: bool,
+    /// Refuse to book any shifts if they violate working-time rules instead of printing warnings
+    #[arg(long)]
+    strict: bool,
+    /// Add the break and clock out that are missing today, so the shifts already recorded add up
+    /// to <DURATION> or the default duration. Recorded shifts and breaks are not changed.
+    #[arg(long, conflicts_with_all(["now", "start", "from", "force", "fill_missing", "complete_date"]))]
+    complete_today: bool,
+    /// Like <COMPLETE_TODAY>, but for the given day. (DD.MM.YYYY or YYYY-MM-DD)
+    #[arg(long, conflicts_with_all(["now", "start", "from", "force", "fill_missing"]))]
+    complete_date: Option<String>,
+    /// The maximum amount of days that are booked at the same time
+    #[arg(short, long, default_value = "4", value_parser = clap::value_parser!(u16).range(1..))]
+    jobs: u16,
 }
 
 impl Auto {
     fn run(&self, api: FactorialApi) {
-        let mut start: chrono::DateTime<Local>;
-        let duration: chrono::Duration;
+        if self.complete_today || self.complete_date.is_some() {
+            return self.complete(api);
+        }
+        let start: chrono::DateTime<Local>;
+        let start_time: Option<NaiveTime>;
+        let duration: Option<chrono::Duration>;
         let mut from: chrono::DateTime<Local>;
         let to: chrono::DateTime<Local>;
         let config = &api.config;
@@ -275,35 +359,36 @@ impl Auto {
         if self.start != "" {
             start = match time::parse_date_time(&self.start) {
                 Ok(t) => t,
-                Err(_) => {
-                    eprintln!("{}", TIME_ERR_MSG);
+                Err(err) => {
+                    eprintln!("{err}\n{TIME_ERR_MSG}");
                     exit(0)
                 }
             };
+            start_time = Some(start.time());
         } else {
             start = Local::now();
+            // Use the start time of the schedule for each day unless told to start now.
+            start_time = if self.now { Some(start.time()) } else { None };
         }
 
         if self.duration != "" {
-            duration = match time::parse_duration(&self.duration) {
-                Ok(d) => d,
-                Err(_) => {
-                    eprintln!("{}", DUR_ERR_MSG);
-                    exit(0)
-                }
-            }
+            duration = Some(parse_duration_arg(&self.duration))
         } else if self.end != "" {
             let end_date = match time::parse_date_time(&self.end) {
                 Ok(t) => t,
-                Err(_) => {
-                    eprintln!("{}", TIME_ERR_MSG);
+                Err(err) => {
+                    eprintln!("{err}\n{TIME_ERR_MSG}");
                     exit(0)
                 }
             };
-            duration = end_date.signed_duration_since(start);
+            let end = time::end_after(start, end_date).unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                exit(0)
+            });
+            duration = Some(end.signed_duration_since(start));
         } else {
-            let dur_secs = config.shift_duration * 60.0 * 60.0;
-            duration = chrono::Duration::seconds(dur_secs.floor() as i64);
+            // Use the default duration of the contract that applies to each day.
+            duration = None;
         }
 
         if self.from != "" {
@@ -322,55 +407,1024 @@ impl Auto {
                 }
             };
         } else {
-            let now = Local::now()
-                .with_time(NaiveTime::from_hms_opt(0, 0, 0).unwrap())
-                .unwrap();
-            from = now;
-            to = now;
+            let today = time::parse_date("today").unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                exit(0)
+            });
+            from = today;
+            to = today;
         }
 
-        let mut free_days = api.get_free_days(from, to).unwrap();
+        let mut free_days = api.get_free_days(from, to).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(0)
+        });
         free_days.sort_unstable();
+        let (pending_leaves, approved_free_days): (Vec<time::FreeDay>, Vec<time::FreeDay>) =
+            free_days.iter().cloned().partition(|d| d.is_pending());
+        if config.pending_leaves == PendingLeaves::Warn {
+            free_days = approved_free_days;
+        }
+
+        // The days that already have attendance are left alone when filling the gaps. The day
+        // before is needed to tell whether the first day starts with the end of a night shift.
+        let mut booked_days: Vec<chrono::NaiveDate> = Vec::new();
+        if self.fill_missing {
+            let day_before = from.checked_sub_days(chrono::Days::new(1)).unwrap();
+            let shifts = api.get_shifts(day_before, to).unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                exit(0)
+            });
+            booked_days = shifts
+                .iter()
+                .map(|s| time::workday_of(s, &shifts))
+                .collect();
+        }
 
+        let mut work_days: Vec<(FactorialApi, time::WorkDay)> = Vec::new();
+        let mut results: Vec<(chrono::NaiveDate, DayResult)> = Vec::new();
         while from <= to {
-            let needle = free_days.binary_search(&from.into());
-            match needle {
-                Ok(num) => {
-                    if free_days.get(num).unwrap().half == time::HalfDay::WholeDay {
+            if booked_days.contains(&from.date_naive()) {
+                results.push((
+                    from.date_naive(),
+                    DayResult::Skipped("Attendance is already recorded".to_string()),
+                ));
+                from = from.checked_add_days(chrono::Days::new(1)).unwrap();
+                continue;
+            }
+
+            for pending in pending_leaves.iter().filter(|d| **d == from) {
+                match config.pending_leaves {
+                    PendingLeaves::Skip => println!(
+                        "{}: {} is not approved yet, treating it as approved.",
+                        from.format("%Y-%m-%d"),
+                        pending.reason
+                    ),
+                    PendingLeaves::Warn => eprintln!(
+                        "Warning: {}: {} is not approved yet, booking the day anyway.",
+                        from.format("%Y-%m-%d"),
+                        pending.reason
+                    ),
+                }
+            }
+
+            let schedule = config.schedule_on(from.date_naive());
+            let start = match (start_time, schedule) {
+                (Some(start_time), _) => time::at_time(from.date_naive(), start_time),
+                (None, Some(schedule)) => time::at_time(from.date_naive(), schedule.start),
+                (None, None) => {
+                    results.push((
+                        from.date_naive(),
+                        DayResult::Skipped("No start time given and no schedule for this day. Use --start or add a schedule to the configuration.".to_string()),
+                    ));
+                    from = from.checked_add_days(chrono::Days::new(1)).unwrap();
+                    continue;
+                }
+            };
+            // The start can fall into the hour skipped when the clocks are put forward
+            let start = match start {
+                Ok(start) => start,
+                Err(err) => {
+                    results.push((from.date_naive(), DayResult::Failed(err.to_string())));
+                    from = from.checked_add_days(chrono::Days::new(1)).unwrap();
+                    continue;
+                }
+            };
+            let duration = duration.unwrap_or(config.daily_target(from.date_naive()));
+            let (day_start, day_duration) =
+                match time::remaining_work(start, duration, &free_days, &config.break_policy) {
+                    Some(remaining) => remaining,
+                    None => {
+                        let reasons: Vec<String> = free_days
+                            .iter()
+                            .filter(|d| **d == from)
+                            .map(|d| d.reason.to_string())
+                            .collect();
+                        results.push((from.date_naive(), DayResult::Skipped(reasons.join(", "))));
+                        from = from.checked_add_days(chrono::Days::new(1)).unwrap();
+                        continue;
+                    }
+                };
+
+            let mut work_day: time::WorkDay;
+            if self.randomize {
+                work_day = time::WorkDay::randomize_shift(
+                    day_start,
+                    day_duration,
+                    &config.breaks,
+                    config.max_rand_range,
+                    &config.break_policy,
+                );
+            } else {
+                work_day = time::WorkDay::plan(
+                    day_start,
+                    day_duration,
+                    &config.breaks,
+                    &config.break_policy,
+                );
+            }
+            let break_window = schedule
+                .and_then(|s| s.break_window)
+                .or(config.break_window);
+            if let Some(window) = break_window {
+                // The window of a night shift can be on the next day
+                let window_times = time::at_time(from.date_naive(), window.end)
+                    .and_then(|end| time::end_after(day_start, end))
+                    .and_then(|end| Ok((time::at_time(end.date_naive(), window.start)?, end)));
+                match window_times {
+                    Ok((window_start, window_end)) => work_day.place_break_in_window(
+                        window_start,
+                        window_end,
+                        &config.break_policy,
+                    ),
+                    Err(err) => {
+                        results.push((from.date_naive(), DayResult::Failed(err.to_string())));
                         from = from.checked_add_days(chrono::Days::new(1)).unwrap();
                         continue;
                     }
                 }
-                Err(_) => {}
             }
+            let day_api = match schedule.and_then(|s| s.location_type.as_deref()) {
+                Some(location_type) => api.with_location_type(location_type),
+                None => api.clone(),
+            };
+            work_days.push((day_api, work_day));
 
-            if self.force {
-                api.delete_all_shifts(from).unwrap();
+            from = from.checked_add_days(chrono::Days::new(1)).unwrap();
+        }
+
+        let planned: Vec<time::Shift> = work_days.iter().flat_map(|(_, w)| w.shifts()).collect();
+        check_compliance(&api, &planned, self.force, self.strict);
+
+        // Days are independent of each other, so they can be booked at the same time
+        let booked =
+            run_parallel(
+                &work_days,
+                self.jobs.into(),
+                |(api, work_day)| match Auto::book_day(api, work_day, self.force) {
+                    Ok(()) => DayResult::Booked,
+                    Err(err) => DayResult::Failed(err.to_string()),
+                },
+            );
+        results.extend(
+            work_days
+                .iter()
+                .map(|(_, work_day)| work_day.clock_in().date_naive())
+                .zip(booked),
+        );
+        results.sort_by_key(|(day, _)| *day);
+
+        let (mut succeeded, mut skipped, mut failed) = (0, 0, 0);
+        println!("Day          Result    Reason");
+        for (day, result) in &results {
+            let (status, reason) = match result {
+                DayResult::Booked => {
+                    succeeded += 1;
+                    ("booked", "")
+                }
+                DayResult::Skipped(reason) => {
+                    skipped += 1;
+                    ("skipped", reason.as_str())
+                }
+                DayResult::Failed(reason) => {
+                    failed += 1;
+                    ("failed", reason.as_str())
+                }
+            };
+            println!("{:<12} {:<9} {}", day.format("%Y-%m-%d"), status, reason);
+        }
+        println!("Booked {succeeded} day(s), skipped {skipped}, failed {failed}.");
+    }
+
+    /// Add the missing segments to a day that is partially recorded.
+    fn complete(&self, api: FactorialApi) {
+        let date = self.complete_date.as_deref().unwrap_or("today");
+        let day = time::parse_date(date).unwrap_or_else(|_| {
+            eprintln!("{}", DATE_ERR_MSG);
+            exit(0)
+        });
+        let recorded = api.get_shifts(day, day).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(0)
+        });
+        let first_start = match recorded.iter().map(|s| s.start).min() {
+            Some(start) => start,
+            None => {
+                eprintln!(
+                    "Nothing is recorded on {}. Use auto without --complete-today to book the whole day.",
+                    day.format("%Y-%m-%d")
+                );
+                exit(0)
             }
+        };
+        let duration = match self.duration.as_str() {
+            "" => api.config.daily_target(day.date_naive()),
+            duration => parse_duration_arg(duration),
+        };
+        // Half days off reduce the time to work
+        let free_days = api.get_free_days(day, day).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(0)
+        });
+        let duration =
+            time::remaining_work(first_start, duration, &free_days, &api.config.break_policy)
+                .map(|(_, duration)| duration)
+                .unwrap_or(chrono::Duration::zero());
 
-            let work_day: time::WorkDay;
-            start = from.with_time(start.time()).unwrap();
-            if self.randomize {
-                work_day = time::WorkDay::randomize_shift(start, duration, config.max_rand_range);
+        let segments = time::complete_day(&recorded, duration, &api.config.break_policy);
+        if segments.is_empty() {
+            println!("{} is already complete.", day.format("%Y-%m-%d"));
+            return;
+        }
+        let planned: Vec<time::Shift> = time::WorkDay {
+            segments: segments.clone(),
+        }
+        .shifts();
+        check_compliance(&api, &planned, false, self.strict);
+
+        let result = match recorded.iter().find(|s| s.end.is_none()) {
+            Some(open) => Auto::continue_open(&api, open.is_break, &segments),
+            None => Auto::book_day(
+                &api,
+                &time::WorkDay {
+                    segments: segments.clone(),
+                },
+                false,
+            ),
+        };
+        if let Err(err) = result {
+            eprintln!("{}", err);
+            exit(0)
+        }
+        for segment in &segments {
+            println!(
+                "{} {} - {}",
+                if segment.is_break { "Break" } else { "Shift" },
+                segment.start.format("%H:%M"),
+                segment.end.format("%H:%M")
+            );
+        }
+    }
+
+    /// Continue an open shift or break with the given segments, switching between work and
+    /// break where they change, and clock out at the end.
+    fn continue_open(
+        api: &FactorialApi,
+        open_is_break: bool,
+        segments: &[time::Segment],
+    ) -> anyhow::Result<()> {
+        let mut on_break = open_is_break;
+        for segment in segments {
+            match (on_break, segment.is_break) {
+                (false, true) => api.break_start(segment.start)?,
+                (true, false) => api.break_end(segment.start)?,
+                _ => {}
+            }
+            on_break = segment.is_break;
+        }
+        let end = segments.last().unwrap().end;
+        if on_break {
+            api.break_end(end)?;
+        }
+        api.shift_end(end)
+    }
+
+    /// Book all shifts and breaks of a day, replacing the existing ones of the same workday if
+    /// `force` is set. If a segment can not be booked, the segments booked so far are removed
+    /// again and the replaced ones are restored, so the day is never only partially booked.
+    fn book_day(api: &FactorialApi, work_day: &time::WorkDay, force: bool) -> anyhow::Result<()> {
+        let mut replaced = Vec::new();
+        if force {
+            let day = work_day.clock_in().date_naive();
+            let day_before = work_day
+                .clock_in()
+                .checked_sub_days(chrono::Days::new(1))
+                .unwrap();
+            let shifts = api.get_shifts(day_before, work_day.clock_out())?;
+            replaced = shifts
+                .iter()
+                .filter(|s| time::workday_of(s, &shifts) == day)
+                .cloned()
+                .collect();
+            // The latest parts go first, so the part of a night shift after midnight is never
+            // left without its start for a day booked at the same time
+            replaced.reverse();
+            api.delete_shifts(&replaced)?;
+        }
+        let mut created = Vec::new();
+        for segment in &work_day.segments {
+            let result = if segment.is_break {
+                api.make_break(segment.start, segment.end)
             } else {
-                work_day = time::WorkDay::standard_shift(start, duration);
+                api.make_shift(segment.start, segment.end)
+            };
+            match result {
+                Ok(ids) => created.extend(ids),
+                Err(err) => {
+                    let not_removed = created
+                        .iter()
+                        .filter(|id| api.delete_shift(**id).is_err())
+                        .count();
+                    if not_removed > 0 {
+                        return Err(anyhow!(
+                            "{err} Removing the segments booked so far failed, the day is only partially booked."
+                        ));
+                    }
+                    if api.restore_shifts(&replaced).is_err() {
+                        return Err(anyhow!(
+                            "{err} Restoring the replaced shifts failed, the day is only partially booked."
+                        ));
+                    }
+                    return Err(err);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+/// The result of processing a single day in `auto`.
+enum DayResult {
+    /// All shifts and breaks of the day were booked
+    Booked,
+    /// Nothing had to be booked for the reason given
+    Skipped(String),
+    /// The day could not be booked for the reason given
+    Failed(String),
+}
+/// List the days without work and the reason they are free
+#[derive(Args)]
+struct FreeDays {
+    /// The first day to list. Dates should be formatted like DD.MM.YYYY or YYYY-MM-DD
+    #[arg(long)]
+    from: String,
+    /// The last day to list. (DD.MM.YYYY or YYYY-MM-DD)
+    #[arg(long)]
+    to: String,
+}
+impl FreeDays {
+    fn run(&self, api: FactorialApi) {
+        let (from, to) = match (time::parse_date(&self.from), time::parse_date(&self.to)) {
+            (Ok(from), Ok(to)) => (from, to),
+            _ => {
+                eprintln!("{}", DATE_ERR_MSG);
+                exit(0)
+            }
+        };
+        let mut free_days = api.get_free_days(from, to).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(0)
+        });
+        free_days.sort();
+        for free_day in free_days.iter().filter(|d| d.day >= from && d.day <= to) {
+            let part = match (&free_day.half, free_day.hours) {
+                (_, Some(hours)) => time::format_duration(hours),
+                (time::HalfDay::WholeDay, None) => "whole day".to_string(),
+                (time::HalfDay::StartOfDay, None) => "morning".to_string(),
+                (time::HalfDay::EndOfDay, None) => "afternoon".to_string(),
+            };
+            println!(
+                "{} {:<3} {:<10} {}",
+                free_day.day.format("%Y-%m-%d"),
+                free_day.day.format("%a"),
+                part,
+                free_day.reason
+            );
+        }
+    }
+}
+/// Manage your leaves
+#[derive(Args)]
+struct Leave {
+    #[command(subcommand)]
+    command: LeaveCommands,
+}
+#[derive(Subcommand)]
+enum LeaveCommands {
+    Types(LeaveTypes),
+    Request(LeaveRequest),
+    Balance(LeaveBalance),
+}
+impl Leave {
+    fn run(&self, api: FactorialApi) {
+        match &self.command {
+            LeaveCommands::Types(c) => c.run(api),
+            LeaveCommands::Request(c) => c.run(api),
+            LeaveCommands::Balance(c) => c.run(api),
+        }
+    }
+}
+/// List the types of leave you can request
+#[derive(Args)]
+struct LeaveTypes {}
+impl LeaveTypes {
+    fn run(&self, api: FactorialApi) {
+        let leave_types = api.get_leave_types().unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(0)
+        });
+        print_leave_types(&leave_types);
+    }
+}
+/// Request a leave, e.g. vacation
+#[derive(Args)]
+struct LeaveRequest {
+    /// The type of leave to request, e.g. vacation, sick-leave, training or the name of the leave
+    /// type as shown in Factorial
+    #[arg(long = "type", value_name = "TYPE")]
+    leave_type: String,
+    /// The first day of the leave. Dates should be formatted like DD.MM.YYYY or YYYY-MM-DD
+    #[arg(long)]
+    from: String,
+    /// The last day of the leave, defaults to <FROM>. (DD.MM.YYYY or YYYY-MM-DD)
+    #[arg(long, default_value = "")]
+    to: String,
+    /// Only take half of the day off, either at the start or at the end of the day
+    #[arg(long, value_parser = ["start", "end"])]
+    half_day: Option<String>,
+    /// A note for whoever approves the leave
+    #[arg(long, default_value = "")]
+    note: String,
+}
+impl LeaveRequest {
+    fn run(&self, api: FactorialApi) {
+        let leave_types = api.get_leave_types().unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(0)
+        });
+        print_leave_types(&leave_types);
+        let leave_type = match LeaveType::find(&leave_types, &self.leave_type) {
+            Some(t) => t,
+            None => {
+                eprintln!("Unknown leave type '{}'.", self.leave_type);
+                exit(0)
+            }
+        };
+
+        let from = time::parse_date(&self.from).unwrap_or_else(|_| {
+            eprintln!("{}", DATE_ERR_MSG);
+            exit(0)
+        });
+        let to = match self.to.as_str() {
+            "" => from,
+            to => time::parse_date(to).unwrap_or_else(|_| {
+                eprintln!("{}", DATE_ERR_MSG);
+                exit(0)
+            }),
+        };
+        if from > to {
+            eprintln!("The last day of the leave has to be on or after the first day.");
+            exit(0)
+        }
+        let half = match self.half_day.as_deref() {
+            Some("start") => time::HalfDay::StartOfDay,
+            Some("end") => time::HalfDay::EndOfDay,
+            _ => time::HalfDay::WholeDay,
+        };
+
+        match api.request_leave(leave_type, from, to, half, &self.note) {
+            Ok(_) => println!(
+                "Requested {} from {} to {}.",
+                leave_type.name,
+                from.format("%Y-%m-%d"),
+                to.format("%Y-%m-%d")
+            ),
+            Err(e) => {
+                eprintln!("{}", e);
+                exit(0)
             }
+        }
+    }
+}
+/// Show the allowance, the days taken, planned and remaining per leave type
+#[derive(Args)]
+struct LeaveBalance {
+    /// The year to show the balance for, defaults to the current year
+    #[arg(long)]
+    year: Option<i32>,
+}
+impl LeaveBalance {
+    fn run(&self, api: FactorialApi) {
+        let year = self.year.unwrap_or(Local::now().year());
+        let balances = api.get_leave_balances(year).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(0)
+        });
+        println!(
+            "{:<24} {:>9} {:>7} {:>7} {:>9}",
+            "Leave type", "Allowance", "Taken", "Planned", "Remaining"
+        );
+        for balance in balances {
+            println!(
+                "{:<24} {:>9.1} {:>7.1} {:>7.1} {:>9.1}",
+                balance.leave_type,
+                balance.allowance,
+                balance.taken,
+                balance.planned,
+                balance.remaining
+            );
+        }
+    }
+}
+fn print_leave_types(leave_types: &[LeaveType]) {
+    println!("Available leave types:");
+    for leave_type in leave_types {
+        println!("  {}", leave_type.name);
+    }
+}
+/// Compare the expected working hours with the hours worked
+#[derive(Args)]
+struct Balance {
+    /// Show the balance of a whole month. The month should be formatted like YYYY-MM
+    #[arg(long, conflicts_with_all(["from", "to"]))]
+    month: Option<String>,
+    /// The first day of the period, defaults to the first day of the current month. Dates should
+    /// be formatted like DD.MM.YYYY or YYYY-MM-DD
+    #[arg(long)]
+    from: Option<String>,
+    /// The last day of the period, defaults to today. (DD.MM.YYYY or YYYY-MM-DD)
+    #[arg(long)]
+    to: Option<String>,
+}
+impl Balance {
+    fn run(&self, api: FactorialApi) {
+        let parse = |date: &str| {
+            time::parse_date(date).unwrap_or_else(|_| {
+                eprintln!("{}", DATE_ERR_MSG);
+                exit(0)
+            })
+        };
+        let today = parse("today");
+        let (from, to) = match &self.month {
+            Some(month) => parse_month(month),
+            None => (
+                self.from
+                    .as_deref()
+                    .map(parse)
+                    .unwrap_or(today.with_day(1).unwrap()),
+                self.to.as_deref().map(parse).unwrap_or(today),
+            ),
+        };
 
-            // Don't take a break if it's 0 minutes long
-            if work_day.break_start == work_day.break_end {
-                api.make_shift(work_day.clock_in, work_day.clock_out)
-                    .unwrap();
+        let free_days = api.get_free_days(from, to).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(0)
+        });
+        // The day before tells whether the first day starts with the end of a night shift
+        let day_before = from.checked_sub_days(chrono::Days::new(1)).unwrap();
+        let shifts = api.get_shifts(day_before, to).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(0)
+        });
+        let days = balance::daily_balances(
+            from,
+            to,
+            |day| api.config.daily_target(day),
+            &free_days,
+            &shifts,
+            &api.config.break_policy,
+        );
+        let mut weeks = balance::weekly_balances(&days).into_iter();
+        let mut months = balance::monthly_balances(&days).into_iter();
+
+        let row = |label: &str, balance: &balance::Balance, total: chrono::Duration| {
+            println!(
+                "{:<12} {:>9} {:>9} {:>9} {:>9}",
+                label,
+                time::format_duration(balance.expected),
+                time::format_duration(balance.worked),
+                format_surplus(balance.surplus()),
+                format_surplus(total)
+            )
+        };
+        println!(
+            "{:<12} {:>9} {:>9} {:>9} {:>9}",
+            "", "Expected", "Worked", "Balance", "Total"
+        );
+        let mut total = chrono::Duration::zero();
+        for (i, (day, day_balance)) in days.iter().enumerate() {
+            total += day_balance.surplus();
+            row(&day.format("%Y-%m-%d").to_string(), day_balance, total);
+
+            let next = days.get(i + 1).map(|(d, _)| *d);
+            if next.map(|d| d.iso_week() != day.iso_week()).unwrap_or(true) {
+                let ((_, week), week_balance) = weeks.next().unwrap();
+                row(&format!("Week {week}"), &week_balance, total);
+            }
+            if next.map(|d| d.month() != day.month()).unwrap_or(true) {
+                let (_, month_balance) = months.next().unwrap();
+                row(&day.format("%B %Y").to_string(), &month_balance, total);
+            }
+        }
+    }
+}
+/// Show whether you are currently working or on a break and how long you worked today
+#[derive(Args)]
+struct Status {}
+impl Status {
+    fn run(&self, api: FactorialApi) {
+        let now = Local::now();
+        close_forgotten_shifts(&api, now);
+        let shifts = api.get_shifts(now, now).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(0)
+        });
+        let open_shift = shifts.iter().find(|s| !s.is_break && s.end.is_none());
+        let open_break = shifts.iter().find(|s| s.is_break && s.end.is_none());
+        match (open_shift, open_break) {
+            (_, Some(open_break)) => {
+                println!("On a break since {}.", open_break.start.format("%H:%M"))
+            }
+            (Some(open_shift), None) => {
+                println!("Working since {}.", open_shift.start.format("%H:%M"))
+            }
+            (None, None) => println!("Not clocked in."),
+        }
+        let worked = shifts.iter().filter(|s| !s.is_break).fold(
+            chrono::Duration::zero(),
+            |worked, s| match s.end {
+                Some(_) => worked + s.duration(),
+                None => worked + (now - s.start),
+            },
+        );
+        println!(
+            "Worked {} of {} today.",
+            time::format_duration(worked),
+            time::format_duration(api.config.daily_target(now.date_naive()))
+        );
+    }
+}
+/// Get the shifts and breaks that are open at `time` as if they were closed at `time`, so they can
+/// be checked before they are actually closed.
+fn open_shifts_closed_at(api: &FactorialApi, time: DateTime<Local>) -> Vec<time::Shift> {
+    api.get_shifts(time.checked_sub_days(chrono::Days::new(1)).unwrap(), time)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|s| s.end.is_none() && s.start < time)
+        .map(|s| time::Shift {
+            end: Some(time),
+            ..s
+        })
+        .collect()
+}
+/// Look for shifts of previous days that were never closed and offer to close them, either at a
+/// time entered by the user or after the default duration. If stdin is not a terminal, the shift
+/// is only reported and left open.
+fn close_forgotten_shifts(api: &FactorialApi, now: DateTime<Local>) {
+    let open_shifts = api.get_open_shifts(now).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        exit(0)
+    });
+    let open_shift = match open_shifts.iter().find(|s| !s.is_break) {
+        Some(open_shift) => open_shift,
+        None => return,
+    };
+    // Without a terminal there is nobody to ask, so the shift is left open
+    if !std::io::stdin().is_terminal() {
+        eprintln!(
+            "The shift started on {} was never closed. Close it with shift-end --time.",
+            open_shift.start.format("%Y-%m-%d %H:%M")
+        );
+        return;
+    }
+    let default_end = open_shift.start + api.config.daily_target(open_shift.start.date_naive());
+    println!(
+        "The shift started on {} was never closed. Enter the time to close it at (HH:MM), leave empty to close it at {} or enter 'n' to leave it open: ",
+        open_shift.start.format("%Y-%m-%d %H:%M"),
+        default_end.format("%H:%M")
+    );
+    let mut buffer = String::new();
+    // The end of the input leaves the shift open, like 'n'
+    if matches!(std::io::stdin().read_line(&mut buffer), Err(_) | Ok(0)) {
+        return;
+    }
+    let end = match buffer.trim() {
+        "" => default_end,
+        "n" | "N" => return,
+        input => {
+            let time = time::parse_date_time(input).unwrap_or_else(|err| {
+                eprintln!("{err}\n{TIME_ERR_MSG}");
+                exit(0)
+            });
+            // A time without a day is on the day the shift started, or after midnight if it is
+            // before the start
+            if time::names_day(input) {
+                time
             } else {
-                api.make_shift(work_day.clock_in, work_day.break_start)
-                    .unwrap();
-                api.make_break(work_day.break_start, work_day.break_end)
-                    .unwrap();
-                api.make_shift(work_day.break_end, work_day.clock_out)
-                    .unwrap();
+                let end = time::on_day(time, open_shift.start).unwrap_or_else(|err| {
+                    eprintln!("{}", err);
+                    exit(0)
+                });
+                time::end_after(open_shift.start, end).unwrap_or_else(|err| {
+                    eprintln!("{}", err);
+                    exit(0)
+                })
             }
+        }
+    };
+    if open_shifts.iter().any(|s| s.is_break) {
+        api.break_end(end).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(0)
+        });
+    }
+    api.shift_end(end).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        exit(0)
+    });
+    println!("Closed the shift at {}.", end.format("%Y-%m-%d %H:%M"));
+}
+/// Parse the duration of a shift or break. Exits if it can't be parsed or is not positive.
+fn parse_duration_arg(duration: &str) -> chrono::Duration {
+    match time::parse_duration(duration) {
+        Ok(d) if d > chrono::Duration::zero() => d,
+        Ok(_) => {
+            eprintln!("The duration of a shift or break has to be longer than zero.");
+            exit(0)
+        }
+        Err(err) => {
+            eprintln!("{err}\n{DUR_ERR_MSG}");
+            exit(0)
+        }
+    }
+}
 
-            from = from.checked_add_days(chrono::Days::new(1)).unwrap();
+/// Parse `time` on the day given by `date` or on today if there is none. Exits if either can't be
+/// parsed, if `time` already names a day or is relative to now while a date is given, or if the
+/// time does not exist on that day.
+fn parse_time_on(time: &str, date: Option<&str>) -> DateTime<Local> {
+    if date.is_some() && time::names_day(time) {
+        eprintln!("--date can't be used with '{time}', which already names a day or is relative to now. Give only a time of day like 17:45.");
+        exit(0)
+    }
+    let time = time::parse_date_time(time).unwrap_or_else(|err| {
+        eprintln!("{err}\n{TIME_ERR_MSG}");
+        exit(0)
+    });
+    match date {
+        Some(date) => match time::parse_date(date) {
+            Ok(day) => time::on_day(time, day).unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                exit(0)
+            }),
+            Err(_) => {
+                eprintln!("{}", DATE_ERR_MSG);
+                exit(0)
+            }
+        },
+        None => time,
+    }
+}
+
+/// Check the shifts that are about to be booked together with the existing shifts around them
+/// against working-time rules. Existing shifts on the booked days are left out if they are going
+/// to be replaced. Violations are printed as warnings or, if `strict` is set, nothing is booked.
+fn check_compliance(api: &FactorialApi, planned: &[time::Shift], replace: bool, strict: bool) {
+    let (from, to) = match (
+        planned.iter().map(|s| s.start).min(),
+        planned.iter().map(|s| s.start).max(),
+    ) {
+        (Some(from), Some(to)) => (
+            from.checked_sub_days(chrono::Days::new(1)).unwrap(),
+            to.checked_add_days(chrono::Days::new(1)).unwrap(),
+        ),
+        _ => return,
+    };
+    let booked_days: Vec<chrono::NaiveDate> = planned
+        .iter()
+        .map(|s| time::workday_of(s, planned))
+        .collect();
+    let mut shifts = api.get_shifts(from, to).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        exit(0)
+    });
+    if replace {
+        let existing = shifts.clone();
+        shifts.retain(|s| !booked_days.contains(&time::workday_of(s, &existing)));
+    }
+    shifts.extend(planned.iter().cloned());
+    let free_days = api.get_free_days(from, to).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        exit(0)
+    });
+
+    // Only report the booked days and the rest on the day after them
+    let violations: Vec<compliance::Violation> =
+        compliance::check(&shifts, &free_days, &api.config.break_policy)
+            .into_iter()
+            .filter(|v| {
+                booked_days.contains(&v.day())
+                    || matches!(v, compliance::Violation::MinimumRest { day, .. } if booked_days.contains(&day.pred_opt().unwrap()))
+            })
+            .collect();
+    for violation in &violations {
+        eprintln!(
+            "Warning: {}: {}",
+            violation.day().format("%Y-%m-%d"),
+            violation
+        );
+    }
+    if strict && !violations.is_empty() {
+        eprintln!("Nothing was booked because of the violations above. Run without --strict to book anyway.");
+        exit(0)
+    }
+}
+/// Audit the shifts of a month for violations of working-time rules
+#[derive(Args)]
+struct Audit {
+    /// The month to audit, defaults to the current month. The month should be formatted like
+    /// YYYY-MM
+    #[arg(long)]
+    month: Option<String>,
+}
+impl Audit {
+    fn run(&self, api: FactorialApi) {
+        let month = self
+            .month
+            .clone()
+            .unwrap_or(Local::now().format("%Y-%m").to_string());
+        let (from, to) = parse_month(&month);
+        // Include the day before to check the rest before the first day of the month
+        let day_before = from.checked_sub_days(chrono::Days::new(1)).unwrap();
+
+        let free_days = api.get_free_days(day_before, to).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(0)
+        });
+        let shifts = api.get_shifts(day_before, to).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(0)
+        });
+        let violations: Vec<compliance::Violation> =
+            compliance::check(&shifts, &free_days, &api.config.break_policy)
+                .into_iter()
+                .filter(|v| v.day() >= from.date_naive() && v.day() <= to.date_naive())
+                .collect();
+
+        println!("Working-time audit for {}", from.format("%B %Y"));
+        if violations.is_empty() {
+            println!("No violations found.");
+            return;
+        }
+        for violation in &violations {
+            println!("{:<12} {}", violation.day().format("%Y-%m-%d"), violation);
+        }
+        let mut days: Vec<chrono::NaiveDate> = violations.iter().map(|v| v.day()).collect();
+        days.dedup();
+        println!(
+            "{} violation(s) on {} day(s).",
+            violations.len(),
+            days.len()
+        );
+    }
+}
+/// Show or submit the attendance period of a month
+#[derive(Args)]
+struct Period {
+    #[command(subcommand)]
+    command: PeriodCommands,
+}
+#[derive(Subcommand)]
+enum PeriodCommands {
+    Show(PeriodShow),
+    Submit(PeriodSubmit),
+}
+impl Period {
+    fn run(&self, api: FactorialApi) {
+        match &self.command {
+            PeriodCommands::Show(c) => c.run(api),
+            PeriodCommands::Submit(c) => c.run(api),
+        }
+    }
+}
+/// Show the state of the attendance period and the time expected and worked in it
+#[derive(Args)]
+struct PeriodShow {
+    /// The month of the period, defaults to the current month. The month should be formatted
+    /// like YYYY-MM
+    #[arg(long)]
+    month: Option<String>,
+}
+impl PeriodShow {
+    fn run(&self, api: FactorialApi) {
+        let month = self
+            .month
+            .clone()
+            .unwrap_or(Local::now().format("%Y-%m").to_string());
+        let (from, to) = parse_month(&month);
+        let period = api.get_period(from).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(0)
+        });
+        // Calculate the totals ourselves if Factorial does not provide them
+        let total = match (period.expected, period.worked) {
+            (Some(expected), Some(worked)) => balance::Balance { expected, worked },
+            _ => {
+                let free_days = api.get_free_days(from, to).unwrap_or_else(|err| {
+                    eprintln!("{}", err);
+                    exit(0)
+                });
+                let shifts = api.get_shifts(from, to).unwrap_or_else(|err| {
+                    eprintln!("{}", err);
+                    exit(0)
+                });
+                balance::daily_balances(
+                    from,
+                    to,
+                    |day| api.config.daily_target(day),
+                    &free_days,
+                    &shifts,
+                    &api.config.break_policy,
+                )
+                .into_iter()
+                .fold(balance::Balance::default(), |total, (_, b)| total + b)
+            }
+        };
+        println!("{:<10} {}", "Period:", period.name());
+        println!("{:<10} {}", "State:", period.state);
+        println!(
+            "{:<10} {}",
+            "Expected:",
+            time::format_duration(total.expected)
+        );
+        println!("{:<10} {}", "Worked:", time::format_duration(total.worked));
+        println!("{:<10} {}", "Balance:", format_surplus(total.surplus()));
+    }
+}
+/// Submit the attendance period for approval by your manager
+#[derive(Args)]
+struct PeriodSubmit {
+    /// The month of the period, defaults to the current month. The month should be formatted
+    /// like YYYY-MM
+    #[arg(long)]
+    month: Option<String>,
+}
+impl PeriodSubmit {
+    fn run(&self, api: FactorialApi) {
+        let month = self
+            .month
+            .clone()
+            .unwrap_or(Local::now().format("%Y-%m").to_string());
+        let (from, _) = parse_month(&month);
+        let period = api.get_period(from).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(0)
+        });
+        api.submit_period(&period).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(0)
+        });
+        println!(
+            "Submitted the attendance period of {} for approval.",
+            period.name()
+        );
+    }
+}
+/// Call `f` for every item using at most `workers` threads at the same time. The results are in
+/// the same order as the items.
+fn run_parallel<T: Sync, R: Send>(
+    items: &[T],
+    workers: usize,
+    f: impl Fn(&T) -> R + Sync,
+) -> Vec<R> {
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<(usize, R)>> = Mutex::new(Vec::with_capacity(items.len()));
+    std::thread::scope(|scope| {
+        for _ in 0..workers.min(items.len()) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                let item = match items.get(i) {
+                    Some(item) => item,
+                    None => break,
+                };
+                let result = f(item);
+                results.lock().unwrap().push((i, result));
+            });
         }
+    });
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, r)| r).collect()
+}
+/// Get the first and the last day of a month formatted like YYYY-MM.
+fn parse_month(month: &str) -> (DateTime<Local>, DateTime<Local>) {
+    let from = time::parse_date(&format!("{month}-01")).unwrap_or_else(|_| {
+        eprintln!(
+            "Could not parse month. Month has to be in the format of 'year-month', e.g. '2024-05'."
+        );
+        exit(0)
+    });
+    let to = from
+        .checked_add_months(chrono::Months::new(1))
+        .and_then(|d| d.checked_sub_days(chrono::Days::new(1)))
+        .unwrap();
+    (from, to)
+}
+/// Format a balance with an explicit sign, e.g. +1h30m or -0h15m.
+fn format_surplus(surplus: chrono::Duration) -> String {
+    if surplus < chrono::Duration::zero() {
+        time::format_duration(surplus)
+    } else {
+        format!("+{}", time::format_duration(surplus))
     }
 }
 /// Configure tracktorial
@@ -430,9 +1484,17 @@ pub fn parse_args() {
         Commands::BreakStart(c) => c.run(FactorialApi::get_api().unwrap_or_else(err)),
         Commands::BreakEnd(c) => c.run(FactorialApi::get_api().unwrap_or_else(err)),
         Commands::Auto(c) => c.run(FactorialApi::get_api().unwrap_or_else(err)),
+        Commands::FreeDays(c) => c.run(FactorialApi::get_api().unwrap_or_else(err)),
+        Commands::Leave(c) => c.run(FactorialApi::get_api().unwrap_or_else(err)),
+        Commands::Balance(c) => c.run(FactorialApi::get_api().unwrap_or_else(err)),
+        Commands::Audit(c) => c.run(FactorialApi::get_api().unwrap_or_else(err)),
+        Commands::Period(c) => c.run(FactorialApi::get_api().unwrap_or_else(err)),
+        Commands::Status(c) => c.run(FactorialApi::get_api().unwrap_or_else(err)),
         Commands::Config(c) => c.run(),
     }
 }
 
-const DUR_ERR_MSG: &str = "Could not parse duration. Duration has to be in the format of for example '14h30m11s', '14h30m', '14h', '30m', '11s'.";
-const TIME_ERR_MSG: &str = "Could not parse time. Time has to be either in the format of 'year-month-dayThour:minute:second', 'hour:minute:second', 'hour:minute', or 'hour'";
+const DUR_ERR_MSG: &str = "Could not parse duration. Duration has to be in the format of for example '14h30m11s', '14h30m', '14h', '30m', '11s', '7.5h', '90m' or as ISO-8601 duration like 'PT7H30M'.";
+const DATE_ERR_MSG: &str =
+    "Could not parse date. Date has to be in the format of either 'year-month-day' or 'day.month.year'.";
+const TIME_ERR_MSG: &str = "Could not parse time. Time has to be either in the format of 'year-month-dayThour:minute:second', 'hour:minute:second', 'hour:minute', 'hour' or 'hour:minute' followed by am or pm, e.g. '5:30pm'. It can be prefixed with 'today', 'yesterday' or a weekday, e.g. 'yesterday 17:45', or be relative to now, e.g. 'now-5m' or '-10m'.";
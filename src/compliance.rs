@@ -0,0 +1,205 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Weekday};
+
+use crate::{
+    break_policy::BreakPolicy,
+    time::{format_duration, workday_of, FreeDay, FreeDayReason, HalfDay, Shift},
+};
+
+/// The maximum amount of hours that may be worked on a single day.
+pub const MAX_DAILY_HOURS: i64 = 10;
+/// The minimum amount of hours of rest between the end of work on one day and the start on the
+/// next.
+pub const MIN_REST_HOURS: i64 = 11;
+
+/// A working-time rule that is violated on a day.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    /// More than the maximum amount of hours were worked
+    MaxDailyHours { day: NaiveDate, worked: Duration },
+    /// The rest since the end of the last day of work is too short
+    MinimumRest { day: NaiveDate, rest: Duration },
+    /// Worked longer without a break than the break policy allows
+    ContinuousWork {
+        day: NaiveDate,
+        worked: Duration,
+        max: Duration,
+    },
+    /// The breaks are shorter than the break policy requires for the time worked
+    BreakTooShort {
+        day: NaiveDate,
+        taken: Duration,
+        required: Duration,
+        worked: Duration,
+    },
+    /// Work on a sunday
+    Sunday { day: NaiveDate },
+    /// Work on a day without work, e.g. a holiday
+    FreeDay {
+        day: NaiveDate,
+        reason: FreeDayReason,
+    },
+}
+
+impl Violation {
+    /// Get the day the violation occurs on.
+    pub fn day(&self) -> NaiveDate {
+        match self {
+            Violation::MaxDailyHours { day, .. }
+            | Violation::MinimumRest { day, .. }
+            | Violation::ContinuousWork { day, .. }
+            | Violation::BreakTooShort { day, .. }
+            | Violation::Sunday { day }
+            | Violation::FreeDay { day, .. } => *day,
+        }
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::MaxDailyHours { worked, .. } => write!(
+                f,
+                "Worked {}, more than the maximum of {MAX_DAILY_HOURS} hours a day",
+                format_duration(*worked)
+            ),
+            Violation::MinimumRest { rest, .. } => write!(
+                f,
+                "Only {} of rest since the last day of work, at least {MIN_REST_HOURS} hours are required",
+                format_duration(*rest)
+            ),
+            Violation::ContinuousWork { worked, max, .. } => write!(
+                f,
+                "Worked {} without a break, but at most {} are allowed",
+                format_duration(*worked),
+                format_duration(*max)
+            ),
+            Violation::BreakTooShort {
+                taken,
+                required,
+                worked,
+                ..
+            } => write!(
+                f,
+                "Took {} of break, but {} are required for {} of work",
+                format_duration(*taken),
+                format_duration(*required),
+                format_duration(*worked)
+            ),
+            Violation::Sunday { .. } => write!(f, "Working on a sunday"),
+            Violation::FreeDay { reason, .. } => write!(f, "Working on a free day: {reason}"),
+        }
+    }
+}
+
+/// Check the shifts against working-time rules: the maximum amount of hours per day, the minimum
+/// rest between days, the breaks required by the break policy, the maximum time of work without a
+/// break and no work on sundays or whole free days like holidays, approved leaves and non-working
+/// days. Shifts belong to their workday, so the parts of a night shift split at midnight count
+/// for the day it started on. Days with only open shifts or breaks are only checked for work on
+/// free days.
+pub fn check(shifts: &[Shift], free_days: &[FreeDay], policy: &dyn BreakPolicy) -> Vec<Violation> {
+    let mut days: BTreeMap<NaiveDate, Vec<&Shift>> = BTreeMap::new();
+    for shift in shifts.iter().filter(|s| !s.is_break && s.end.is_some()) {
+        days.entry(workday_of(shift, shifts))
+            .or_default()
+            .push(shift);
+    }
+    let mut open_days: Vec<NaiveDate> = shifts
+        .iter()
+        .map(|s| workday_of(s, shifts))
+        .filter(|day| !days.contains_key(day))
+        .collect();
+    open_days.sort();
+    open_days.dedup();
+
+    let mut violations = Vec::new();
+    let mut last_end = None;
+    for (day, shifts) in days {
+        let worked = shifts
+            .iter()
+            .fold(Duration::zero(), |worked, s| worked + s.duration());
+        let first_start = shifts.iter().map(|s| s.start).min().unwrap();
+        let end = shifts.iter().filter_map(|s| s.end).max().unwrap();
+
+        if worked > Duration::hours(MAX_DAILY_HOURS) {
+            violations.push(Violation::MaxDailyHours { day, worked });
+        }
+        if let Some(last_end) = last_end {
+            let rest = first_start - last_end;
+            if rest < Duration::hours(MIN_REST_HOURS) {
+                violations.push(Violation::MinimumRest { day, rest });
+            }
+        }
+        if let Some(max) = policy.max_work_without_break() {
+            let worked = longest_continuous_work(&shifts);
+            if worked > max {
+                violations.push(Violation::ContinuousWork { day, worked, max });
+            }
+        }
+        // Everything between the first and the last shift of the day that is not work is a break
+        let taken = (end - first_start) - worked;
+        let required = policy.break_duration(worked);
+        if taken < required {
+            violations.push(Violation::BreakTooShort {
+                day,
+                taken,
+                required,
+                worked,
+            });
+        }
+        violations.extend(free_day_violations(day, free_days));
+        last_end = Some(end);
+    }
+    for day in open_days {
+        violations.extend(free_day_violations(day, free_days));
+    }
+    violations.sort_by_key(|v| v.day());
+    violations
+}
+
+/// Get the violations for working on the given day if it is a sunday or a whole free day.
+fn free_day_violations(day: NaiveDate, free_days: &[FreeDay]) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let sunday = day.weekday() == Weekday::Sun;
+    if sunday {
+        violations.push(Violation::Sunday { day });
+    }
+    // Half days and hourly absences leave part of the day to work. A sunday is not reported again
+    // for being a non-working day.
+    for free_day in free_days.iter().filter(|f| {
+        f.day.date_naive() == day
+            && f.half == HalfDay::WholeDay
+            && f.hours.is_none()
+            && !f.is_pending()
+            && !(sunday && f.reason == FreeDayReason::NonWorkingDay)
+    }) {
+        violations.push(Violation::FreeDay {
+            day,
+            reason: free_day.reason.clone(),
+        });
+    }
+    violations
+}
+
+/// Get the longest time worked without a break. Shifts that follow each other without a gap count
+/// as one.
+fn longest_continuous_work(shifts: &[&Shift]) -> Duration {
+    let mut shifts = shifts.to_vec();
+    shifts.sort_by_key(|s| s.start);
+    let mut longest = Duration::zero();
+    let mut current: Option<(DateTime<Local>, DateTime<Local>)> = None;
+    for shift in shifts {
+        let end = shift.end.unwrap_or(shift.start);
+        current = match current {
+            Some((start, current_end)) if shift.start <= current_end => {
+                Some((start, current_end.max(end)))
+            }
+            _ => Some((shift.start, end)),
+        };
+        let (start, end) = current.unwrap();
+        longest = longest.max(end - start);
+    }
+    longest
+}
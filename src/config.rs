@@ -1,13 +1,17 @@
 use std::{
+    collections::BTreeMap,
     fs::{self, File},
     io::Write,
     path::PathBuf,
 };
 
 use anyhow::anyhow;
+use chrono::{Datelike, NaiveDate, NaiveTime, Weekday};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
 
+use crate::break_policy::BreakPolicyConfig;
+
 /// Represents the applications Configuration
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub struct Configuration {
@@ -34,6 +38,177 @@ pub struct Configuration {
     /// specified time if the randomization option is enabled.
     #[serde(default = "default_rand_range")]
     pub max_rand_range: u16,
+    /// What the `auto` sub command does on days with leave requests that have not been approved
+    /// yet.
+    #[serde(default = "default_pending_leaves")]
+    pub pending_leaves: PendingLeaves,
+    /// All versions of the user's contract ordered by the date they became effective. Retrieved
+    /// from Factorial and cached here, removing them makes tracktorial retrieve them again.
+    #[serde(default = "default_contracts")]
+    pub contracts: Vec<ContractVersion>,
+    /// The day the contract versions were last retrieved from Factorial
+    #[serde(default = "default_contracts_checked_on")]
+    pub contracts_checked_on: Option<NaiveDate>,
+    /// Schedules for individual days of the week by the name of the day, e.g. a shorter friday.
+    /// Days without a schedule use `shift_duration` and `location_type`.
+    #[serde(default = "default_schedule")]
+    pub schedule: BTreeMap<String, DaySchedule>,
+    /// The rules for how long breaks have to be, either the name of a country or a custom table
+    /// of thresholds.
+    #[serde(default = "default_break_policy")]
+    pub break_policy: BreakPolicyConfig,
+    /// The breaks to take every day in the order they are taken. Without any breaks a single
+    /// break is taken in the middle of the shift.
+    #[serde(default = "default_breaks")]
+    pub breaks: Vec<PlannedBreak>,
+    /// The time span to take the longest break in, e.g. a lunch break from 12:00 to 13:30. The
+    /// break window of a day's schedule takes precedence.
+    #[serde(default = "default_break_window")]
+    pub break_window: Option<BreakWindow>,
+    /// The amount of minutes responses of Factorial that rarely change, e.g. attendance periods
+    /// and holidays, are persisted for. They are only kept while tracktorial runs if set to 0.
+    #[serde(default = "default_cache_ttl")]
+    pub cache_ttl: u32,
+}
+
+/// The preferred way to work on a certain day of the week.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct DaySchedule {
+    /// The time to clock in
+    pub start: NaiveTime,
+    /// The amount of hours to work
+    pub duration: f32,
+    /// The time span the break should be taken in
+    #[serde(default)]
+    pub break_window: Option<BreakWindow>,
+    /// The location to work from, defaults to `location_type`
+    #[serde(default)]
+    pub location_type: Option<String>,
+}
+
+/// A time span during the day to take a break in, e.g. from 12:00 to 13:30.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct BreakWindow {
+    /// The earliest time to start the break
+    pub start: NaiveTime,
+    /// The latest time to end the break
+    pub end: NaiveTime,
+}
+
+/// A break to take once a certain amount of work is done, e.g. a short break after two hours and
+/// a lunch break after four hours.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct PlannedBreak {
+    /// The amount of hours to work before taking the break
+    pub after_hours: f32,
+    /// The length of the break in minutes
+    pub minutes: u32,
+}
+
+/// A version of the user's contract that applies from a certain date on until the next version
+/// becomes effective.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ContractVersion {
+    /// The first day the contract version applies to
+    pub effective_on: NaiveDate,
+    /// The amount of working hours per `working_hours_frequency`
+    pub working_hours: f32,
+    /// Whether `working_hours` are per day, week or month
+    #[serde(default = "default_frequency")]
+    pub working_hours_frequency: HoursFrequency,
+    /// The days of the week to work on
+    pub working_week_days: Vec<String>,
+}
+
+/// The period contracted working hours refer to.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum HoursFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl ContractVersion {
+    /// Reads a contract version as returned by Factorial. Factorial stores the working hours in
+    /// hundredths of an hour, e.g. 3750 for 37.5 hours.
+    ///
+    /// # Errors
+    /// Returns an error if the working hours or their frequency could not be parsed.
+    pub fn from_factorial(contract: &serde_json::Value) -> anyhow::Result<ContractVersion> {
+        let effective_on = contract["effective_on"]
+            .as_str()
+            .or(contract["starts_on"].as_str())
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+            .unwrap_or(NaiveDate::MIN);
+
+        let hundredths = match &contract["working_hours"] {
+            serde_json::Value::Number(hours) => hours.as_f64(),
+            serde_json::Value::String(hours) => hours.trim().parse::<f64>().ok(),
+            _ => None,
+        };
+        let working_hours = match hundredths {
+            Some(hundredths) if hundredths >= 0.0 => (hundredths / 100.0) as f32,
+            _ => {
+                return Err(anyhow!(
+                    "The amount of working hours in the contract could not be parsed: {}",
+                    contract["working_hours"]
+                ))
+            }
+        };
+
+        let working_hours_frequency = match contract["working_hours_frequency"].as_str() {
+            None | Some("weekly") | Some("week") => HoursFrequency::Weekly,
+            Some("daily") | Some("day") => HoursFrequency::Daily,
+            Some("monthly") | Some("month") => HoursFrequency::Monthly,
+            Some(other) => {
+                return Err(anyhow!(
+                    "Working hours with a frequency of '{other}' are not supported."
+                ))
+            }
+        };
+
+        let working_week_days = contract["working_week_days"]
+            .as_str()
+            .unwrap_or("")
+            .split(',')
+            .map(|s| s.trim().to_owned())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        Ok(ContractVersion {
+            effective_on,
+            working_hours,
+            working_hours_frequency,
+            working_week_days,
+        })
+    }
+
+    /// Get the amount of hours to work per working day. Monthly hours are spread evenly across
+    /// the 52 weeks of a year.
+    pub fn daily_hours(&self) -> f32 {
+        let days_per_week = self.working_week_days.len().max(1) as f32;
+        match self.working_hours_frequency {
+            HoursFrequency::Daily => self.working_hours,
+            HoursFrequency::Weekly => self.working_hours / days_per_week,
+            HoursFrequency::Monthly => self.working_hours * 12.0 / 52.0 / days_per_week,
+        }
+    }
+
+    /// Get the amount of hours to work per week.
+    pub fn weekly_hours(&self) -> f32 {
+        self.daily_hours() * self.working_week_days.len() as f32
+    }
+}
+
+/// How to handle days with leave requests that still wait for approval.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum PendingLeaves {
+    /// Treat the leave as if it was already approved and don't work on that day
+    Skip,
+    /// Work on that day as if there was no leave, but print a warning
+    Warn,
 }
 impl Configuration {
     /// Generates a default configuration
@@ -46,6 +221,14 @@ impl Configuration {
             working_week_days: default_working_days(),
             shift_duration: default_duration(),
             max_rand_range: default_rand_range(),
+            pending_leaves: default_pending_leaves(),
+            contracts: default_contracts(),
+            contracts_checked_on: default_contracts_checked_on(),
+            schedule: default_schedule(),
+            break_policy: default_break_policy(),
+            breaks: default_breaks(),
+            break_window: default_break_window(),
+            cache_ttl: default_cache_ttl(),
         }
     }
 
@@ -99,6 +282,71 @@ impl Configuration {
         Ok(())
     }
 
+    /// Get the contract version that applies to the given day, if any.
+    pub fn contract_on(&self, day: NaiveDate) -> Option<&ContractVersion> {
+        self.contracts
+            .iter()
+            .rev()
+            .find(|contract| contract.effective_on <= day)
+    }
+
+    /// Whether the given day is one of the working week days of the contract that applied to it.
+    /// Days of the current contract version or without a contract use `working_week_days`.
+    pub fn is_working_day(&self, day: NaiveDate) -> bool {
+        let working_week_days = match self.contract_on(day) {
+            Some(contract) if Some(contract) != self.contracts.last() => {
+                &contract.working_week_days
+            }
+            _ => &self.working_week_days,
+        };
+        working_week_days
+            .iter()
+            .filter_map(|d| d.parse::<Weekday>().ok())
+            .any(|d| d == day.weekday())
+    }
+
+    /// Whether the contract versions have to be retrieved from Factorial on `today`. They are
+    /// retrieved once a day, or right away if there are none yet.
+    pub fn contracts_outdated(&self, today: NaiveDate) -> bool {
+        self.contracts.is_empty() || self.contracts_checked_on != Some(today)
+    }
+
+    /// Update the working hours, the shift duration and the working week days that were taken
+    /// from the `previous` contract version to the `current` one. Manually set values are kept.
+    pub fn follow_contract(&mut self, previous: &ContractVersion, current: &ContractVersion) {
+        if self.working_hours == previous.weekly_hours() {
+            self.working_hours = current.weekly_hours();
+        }
+        if self.shift_duration == previous.daily_hours() {
+            self.shift_duration = current.daily_hours();
+        }
+        if self.working_week_days == previous.working_week_days {
+            self.working_week_days = current.working_week_days.clone();
+        }
+    }
+
+    /// Get the schedule for the day of the week of the given day, if any.
+    pub fn schedule_on(&self, day: NaiveDate) -> Option<&DaySchedule> {
+        self.schedule
+            .iter()
+            .find(|(weekday, _)| weekday.parse::<Weekday>().ok() == Some(day.weekday()))
+            .map(|(_, schedule)| schedule)
+    }
+
+    /// Get the amount of time to work on the given day, not taking holidays or leaves into
+    /// account. Days before the current contract version use the hours of the contract version
+    /// that applied back then, all other days use their schedule or `shift_duration`.
+    pub fn daily_target(&self, day: NaiveDate) -> chrono::Duration {
+        let hours = match (self.contract_on(day), self.schedule_on(day)) {
+            (Some(contract), _) if Some(contract) != self.contracts.last() => {
+                contract.daily_hours()
+            }
+            (_, Some(schedule)) => schedule.duration,
+            _ => self.shift_duration,
+        };
+        chrono::Duration::seconds((hours * 60.0 * 60.0).floor() as i64)
+    }
+
     /// Prompt the user for email address
     /// # Errors
     /// Return an error if the address could not be read from stdin or could not be written to the
@@ -134,3 +382,30 @@ fn default_working_days() -> Vec<String> {
 fn default_rand_range() -> u16 {
     30
 }
+fn default_pending_leaves() -> PendingLeaves {
+    PendingLeaves::Skip
+}
+fn default_contracts() -> Vec<ContractVersion> {
+    Vec::new()
+}
+fn default_contracts_checked_on() -> Option<NaiveDate> {
+    None
+}
+fn default_frequency() -> HoursFrequency {
+    HoursFrequency::Weekly
+}
+fn default_schedule() -> BTreeMap<String, DaySchedule> {
+    BTreeMap::new()
+}
+fn default_break_policy() -> BreakPolicyConfig {
+    BreakPolicyConfig::Germany
+}
+fn default_breaks() -> Vec<PlannedBreak> {
+    Vec::new()
+}
+fn default_break_window() -> Option<BreakWindow> {
+    None
+}
+fn default_cache_ttl() -> u32 {
+    0
+}
@@ -1,5 +1,9 @@
 pub mod api;
+pub mod balance;
+pub mod break_policy;
+pub mod cache;
 pub mod cli;
+pub mod compliance;
 pub mod config;
 pub mod login;
 pub mod time;
@@ -1,7 +1,12 @@
 use anyhow::anyhow;
-use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, Timelike};
 use rand::Rng;
 
+use crate::{
+    break_policy::{BreakPolicy, German},
+    config::PlannedBreak,
+};
+
 /// A day where no work has to be done.
 #[derive(Debug, Clone)]
 pub struct FreeDay {
@@ -9,6 +14,25 @@ pub struct FreeDay {
     pub day: DateTime<Local>,
     /// The part of the day without work
     pub half: HalfDay,
+    /// The amount of time without work if only a few hours of the day are free, e.g. for a
+    /// doctor's appointment. `half` then tells whether the absence is at the start or at the end
+    /// of the day.
+    pub hours: Option<Duration>,
+    /// Why no work has to be done
+    pub reason: FreeDayReason,
+}
+
+impl FreeDay {
+    /// Whether the day is only free if a pending leave request gets approved.
+    pub fn is_pending(&self) -> bool {
+        matches!(
+            self.reason,
+            FreeDayReason::Leave {
+                approved: false,
+                ..
+            }
+        )
+    }
 }
 
 impl PartialOrd for FreeDay {
@@ -37,6 +61,8 @@ impl From<DateTime<Local>> for FreeDay {
         FreeDay {
             day: value,
             half: HalfDay::WholeDay,
+            hours: None,
+            reason: FreeDayReason::NonWorkingDay,
         }
     }
 }
@@ -48,160 +74,823 @@ pub enum HalfDay {
     WholeDay,
 }
 
-/// Get a chrono::Duration from a &str in the format of <hours>h<minutes>m<seconds>.
-pub fn parse_duration(time: &str) -> anyhow::Result<Duration> {
-    let mut time = String::from(time);
-    let mut formatter = String::new();
-    if time.contains('h') {
-        formatter.push_str("%Hh");
+/// The reason a day is free.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FreeDayReason {
+    /// The day is not one of the contracted working week days
+    NonWorkingDay,
+    /// A company holiday with its name
+    Holiday(String),
+    /// A leave and whether it has already been approved
+    Leave { kind: LeaveKind, approved: bool },
+}
+
+impl std::fmt::Display for FreeDayReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NonWorkingDay => write!(f, "Non-working day"),
+            Self::Holiday(name) => write!(f, "Holiday ({name})"),
+            Self::Leave {
+                kind,
+                approved: true,
+            } => write!(f, "{kind}"),
+            Self::Leave {
+                kind,
+                approved: false,
+            } => write!(f, "{kind} (pending approval)"),
+        }
     }
-    if time.contains('m') {
-        formatter.push_str("%Mm");
+}
+
+/// The type of a leave.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LeaveKind {
+    Vacation,
+    SickLeave,
+    Parental,
+    Training,
+    /// Any other type of leave with the name Factorial uses for it
+    Other(String),
+}
+
+impl LeaveKind {
+    /// Get the type of a leave from the name or identifier of its leave type in Factorial.
+    pub fn from_name(name: &str) -> LeaveKind {
+        let lowercase = name.to_lowercase();
+        let matches_any = |needles: &[&str]| needles.iter().any(|n| lowercase.contains(n));
+        if matches_any(&[
+            "vacation",
+            "holiday",
+            "urlaub",
+            "vacaciones",
+            "paid time off",
+        ]) {
+            LeaveKind::Vacation
+        } else if matches_any(&["sick", "illness", "krank", "enfermedad"]) {
+            LeaveKind::SickLeave
+        } else if matches_any(&[
+            "parental",
+            "maternity",
+            "paternity",
+            "eltern",
+            "mutterschutz",
+        ]) {
+            LeaveKind::Parental
+        } else if matches_any(&[
+            "training",
+            "education",
+            "course",
+            "fortbildung",
+            "weiterbildung",
+        ]) {
+            LeaveKind::Training
+        } else {
+            LeaveKind::Other(name.to_string())
+        }
     }
-    if time.contains('s') {
-        formatter.push_str("%Ss");
+}
+
+impl std::fmt::Display for LeaveKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Vacation => write!(f, "Vacation"),
+            Self::SickLeave => write!(f, "Sick leave"),
+            Self::Parental => write!(f, "Parental leave"),
+            Self::Training => write!(f, "Training"),
+            Self::Other(name) => write!(f, "{name}"),
+        }
     }
-    if !formatter.contains('h') {
-        formatter.push_str("%Hh");
-        time.push_str("0h");
+}
+
+/// Get the part of a day that is free, if any. A day can be free for several reasons at once, e.g.
+/// a half day holiday on a weekend, in which case the largest free part is returned. Absences of
+/// only a few hours are not taken into account.
+pub fn free_part_of_day(free_days: &[FreeDay], day: DateTime<Local>) -> Option<HalfDay> {
+    let halves: Vec<&HalfDay> = free_days
+        .iter()
+        .filter(|d| d.day.date_naive() == day.date_naive() && d.hours.is_none())
+        .map(|d| &d.half)
+        .collect();
+    let start_free = halves.contains(&&HalfDay::StartOfDay);
+    let end_free = halves.contains(&&HalfDay::EndOfDay);
+    if halves.contains(&&HalfDay::WholeDay) || (start_free && end_free) {
+        Some(HalfDay::WholeDay)
+    } else if start_free {
+        Some(HalfDay::StartOfDay)
+    } else if end_free {
+        Some(HalfDay::EndOfDay)
+    } else {
+        None
+    }
+}
+
+/// Get the start and duration of the part of a shift that still has to be worked on a day where
+/// half of the day is free. The morning half starts at the original start, the afternoon half
+/// starts where the break of a full shift would have ended. Returns None if the whole day is free.
+pub fn working_half(
+    start: DateTime<Local>,
+    duration: Duration,
+    free: &HalfDay,
+    policy: &dyn BreakPolicy,
+) -> Option<(DateTime<Local>, Duration)> {
+    let half_duration = duration.checked_div(2).unwrap();
+    match free {
+        HalfDay::WholeDay => None,
+        HalfDay::EndOfDay => Some((start, half_duration)),
+        HalfDay::StartOfDay => Some((
+            start + half_duration + policy.break_duration(duration),
+            half_duration,
+        )),
     }
-    if !formatter.contains('m') {
-        formatter.push_str("%Mm");
-        time.push_str("0m");
+}
+
+/// Get the start and duration of the work that remains on a day after taking free days into
+/// account. Half free days cut the shift in half, absences of a few hours shorten it and move
+/// the start back if the absence is at the start of the day. Returns None if there is no work
+/// left to do on that day.
+pub fn remaining_work(
+    start: DateTime<Local>,
+    duration: Duration,
+    free_days: &[FreeDay],
+    policy: &dyn BreakPolicy,
+) -> Option<(DateTime<Local>, Duration)> {
+    let (mut start, mut duration) = match free_part_of_day(free_days, start) {
+        Some(half) => working_half(start, duration, &half, policy)?,
+        None => (start, duration),
+    };
+    let day = start.date_naive();
+    for absence in free_days.iter().filter(|d| d.day.date_naive() == day) {
+        let hours = match absence.hours {
+            Some(hours) => hours,
+            None => continue,
+        };
+        duration -= hours;
+        if absence.half == HalfDay::StartOfDay {
+            start += hours;
+        }
     }
-    if !formatter.contains('s') {
-        formatter.push_str("%Ss");
-        time.push_str("0s");
+    if duration <= Duration::zero() {
+        return None;
     }
-    let naive_time = NaiveTime::parse_from_str(&time, &formatter)?;
-    let duration = naive_time.signed_duration_since(NaiveTime::from_hms_opt(0, 0, 0).unwrap());
-    Ok(duration)
+    Some((start, duration))
+}
+
+/// Get a chrono::Duration from a &str like 7h30m, 7.5h, 90m, 25h or 1h 30m 15s, or from an ISO-8601
+/// duration like PT7H30M or P1DT2H. A leading - makes the duration negative.
+pub fn parse_duration(duration: &str) -> anyhow::Result<Duration> {
+    let input = duration.trim();
+    let (negative, unsigned) = match input.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, input.strip_prefix('+').unwrap_or(input)),
+    };
+    let millis = match unsigned.strip_prefix(['P', 'p']) {
+        Some(iso) => {
+            let (date, time) = match iso.split_once(['T', 't']) {
+                Some((date, time)) => (date, Some(time)),
+                None => (iso, None),
+            };
+            if date.is_empty() && time.is_none_or(str::is_empty) {
+                return Err(anyhow!("The duration '{duration}' has no value."));
+            }
+            let mut millis = 0;
+            if !date.is_empty() {
+                millis += sum_components(date, &[('w', 7 * 24 * 3600), ('d', 24 * 3600)])?;
+            }
+            if let Some(time) = time {
+                let time = sum_components(time, &[('h', 3600), ('m', 60), ('s', 1)])?;
+                millis = millis
+                    .checked_add(time)
+                    .ok_or_else(|| anyhow!("The duration '{duration}' is too long."))?;
+            }
+            millis
+        }
+        None => sum_components(unsigned, &[('h', 3600), ('m', 60), ('s', 1)])?,
+    };
+    Duration::try_milliseconds(if negative { -millis } else { millis })
+        .ok_or_else(|| anyhow!("The duration '{duration}' is too long."))
 }
 
-/// Get a chrono::DateTime<Local> from a &str with the format of either
-/// year-month-dayThours:minutes:seconds or HH:MM:SS if the desired date is today.
+/// Sum up components like 7h or 1.5d with the given units and their length in seconds, the result
+/// is in milliseconds. Every unit may appear only once.
+fn sum_components(components: &str, units: &[(char, u32)]) -> anyhow::Result<i64> {
+    if components.trim().is_empty() {
+        return Err(anyhow!("The duration is empty."));
+    }
+    let mut seconds = 0.0;
+    let mut number = String::new();
+    let mut seen = Vec::new();
+    for c in components.chars() {
+        if c.is_ascii_digit() || c == '.' {
+            number.push(c);
+            continue;
+        }
+        if c.is_whitespace() && number.is_empty() {
+            continue;
+        }
+        let unit = c.to_ascii_lowercase();
+        let Some((_, length)) = units.iter().find(|(u, _)| *u == unit) else {
+            return Err(anyhow!("Unknown unit '{c}' in '{components}'."));
+        };
+        if seen.contains(&unit) {
+            return Err(anyhow!("'{c}' appears more than once in '{components}'."));
+        }
+        let value: f64 = number
+            .parse()
+            .map_err(|_| anyhow!("Expected a number before '{c}' in '{components}'."))?;
+        seconds += value * f64::from(*length);
+        number.clear();
+        seen.push(unit);
+    }
+    if !number.is_empty() {
+        return Err(anyhow!("'{number}' in '{components}' is missing a unit."));
+    }
+    let millis = (seconds * 1000.0).round();
+    if millis >= i64::MAX as f64 {
+        return Err(anyhow!("The duration '{components}' is too long."));
+    }
+    Ok(millis as i64)
+}
+
+/// Get a chrono::DateTime<Local> from a &str. Accepted are year-month-dayThours:minutes:seconds,
+/// a time of today like 17:45:30, 17:45, 17 or 5:45pm, a time on another day like "yesterday 17:45"
+/// or "friday 9:00" and times relative to now like "now", "now-5m" or "-10m". Weekdays refer to
+/// the last such day, which is today if it is that weekday.
 pub fn parse_date_time(date_time: &str) -> anyhow::Result<DateTime<Local>> {
     let today = Local::now();
-    if date_time.contains('T') {
+    let date_time = date_time.trim();
+    if date_time.starts_with(|c: char| c.is_ascii_digit()) && date_time.contains('T') {
         let date_time_with_offset = format!("{date_time}{}", today.offset().to_string());
         let time = DateTime::parse_from_rfc3339(&date_time_with_offset)?;
         return Ok(time.into());
     }
-    match NaiveTime::parse_from_str(date_time, "%H:%M:%S") {
-        Ok(hms) => return Ok(today.with_time(hms).unwrap()),
-        Err(_) => {}
+    let input = date_time.to_lowercase();
+    if let Some(offset) = input.strip_prefix("now") {
+        return add_offset(today, offset.trim());
+    }
+    if input.starts_with(['+', '-']) {
+        return add_offset(today, &input);
+    }
+    let day_and_time = input
+        .split_once(char::is_whitespace)
+        .filter(|(day, _)| day.starts_with(char::is_alphabetic));
+    let (day, time) = match day_and_time {
+        Some((day, time)) => (parse_day(day, today)?, time.trim()),
+        None => {
+            if parse_day(&input, today).is_ok() {
+                return Err(anyhow!(
+                    "'{input}' is missing a time of day, e.g. '{input} 17:45'."
+                ));
+            }
+            (today, input.as_str())
+        }
+    };
+    at_time(day.date_naive(), parse_time_of_day(time)?)
+}
+
+/// Add an offset like +1h or -5m to `time`, nothing is added if the offset is empty.
+fn add_offset(time: DateTime<Local>, offset: &str) -> anyhow::Result<DateTime<Local>> {
+    if offset.is_empty() {
+        return Ok(time);
+    }
+    if !offset.starts_with(['+', '-']) {
+        return Err(anyhow!(
+            "An offset has to start with + or -, e.g. 'now-5m', not '{offset}'."
+        ));
+    }
+    match parse_duration(offset) {
+        Ok(duration) => Ok(time + duration),
+        Err(err) => Err(anyhow!(
+            "Could not parse the offset '{offset}', it should look like -10m, +1h or -1h30m. {err}"
+        )),
+    }
+}
+
+/// Get the day referred to by "today", "yesterday" or the name of a weekday relative to `today`.
+fn parse_day(day: &str, today: DateTime<Local>) -> anyhow::Result<DateTime<Local>> {
+    let days_back = match day {
+        "today" => 0,
+        "yesterday" => 1,
+        _ => match day.parse::<chrono::Weekday>() {
+            Ok(weekday) => {
+                (today.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7
+            }
+            Err(_) => {
+                return Err(anyhow!(
+                    "Unknown day '{day}', use today, yesterday or the name of a weekday."
+                ))
+            }
+        },
+    };
+    Ok(today
+        .checked_sub_days(chrono::Days::new(days_back.into()))
+        .unwrap())
+}
+
+/// Get the time of day from HH:MM:SS, HH:MM or HH, optionally followed by am or pm.
+fn parse_time_of_day(time: &str) -> anyhow::Result<NaiveTime> {
+    let (clock, pm) = match (time.strip_suffix("am"), time.strip_suffix("pm")) {
+        (Some(clock), _) => (clock.trim(), Some(false)),
+        (_, Some(clock)) => (clock.trim(), Some(true)),
+        _ => (time, None),
     };
-    match NaiveTime::parse_from_str(date_time, "%H:%M") {
-        Ok(hm) => return Ok(today.with_time(hm).unwrap()),
-        Err(_) => {}
+    let parsed = ["%H:%M:%S", "%H:%M"]
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(clock, format).ok())
+        .or_else(|| NaiveTime::parse_from_str(&format!("{clock}:00"), "%H:%M").ok());
+    let Some(parsed) = parsed else {
+        return Err(anyhow!("Could not parse the time '{time}'."));
     };
-    match NaiveTime::parse_from_str(format!("{}:00", date_time).as_str(), "%H:%M") {
-        Ok(hours) => return Ok(today.with_time(hours).unwrap()),
-        Err(_) => {}
+    let Some(pm) = pm else {
+        return Ok(parsed);
     };
-    Err(anyhow!("Could not parse the time."))
+    if parsed.hour() == 0 || parsed.hour() > 12 {
+        return Err(anyhow!(
+            "'{time}' is ambiguous, hours with am or pm have to be between 1 and 12."
+        ));
+    }
+    let hour = parsed.hour() % 12 + if pm { 12 } else { 0 };
+    Ok(parsed.with_hour(hour).unwrap())
 }
 
-/// Get a chrono::DateTime<Local> from a &str with the format YYYY-mm-dd or dd.mm.YYYY. The
-/// time at that date will be 00:00:00
+/// Get a chrono::DateTime<Local> from a &str with the format YYYY-mm-dd or dd.mm.YYYY or from
+/// "today" or "yesterday". The time at that date will be 00:00:00
+///
+/// # Errors
+/// Returns an Error if the date can't be parsed or midnight does not exist on that day.
 pub fn parse_date(date: &str) -> anyhow::Result<DateTime<Local>> {
-    let midnight = NaiveTime::from_hms_opt(0, 0, 0).unwrap();
-    match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
-        Ok(ymd) => {
-            let naive_dt = NaiveDateTime::from(ymd);
-            let local_dt: DateTime<Local> = naive_dt.and_utc().into();
-            return Ok(local_dt.with_time(midnight).unwrap());
-        }
-        Err(_) => {}
+    let today = Local::now().date_naive();
+    let day = match date {
+        "today" => today,
+        "yesterday" => today.pred_opt().unwrap(),
+        _ => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .or(NaiveDate::parse_from_str(date, "%d.%m.%Y"))
+            .map_err(|_| anyhow!("Could not parse date."))?,
     };
-    match NaiveDate::parse_from_str(date, "%d.%m.%Y") {
-        Ok(ymd) => {
-            let naive_dt = NaiveDateTime::from(ymd);
-            let local_dt: DateTime<Local> = naive_dt.and_utc().into();
-            return Ok(local_dt.with_time(midnight).unwrap());
-        }
-        Err(_) => {}
-    }
-    Err(anyhow!("Could not parse date."))
+    at_time(day, NaiveTime::MIN)
 }
 
 /// Get the mandatory duration for a break depending on the duration of work as required by german
 /// law.
 pub fn get_break_duration(work_duration: chrono::Duration) -> chrono::Duration {
-    let mut break_duration = chrono::Duration::minutes(0);
-    if work_duration.num_hours() >= 6 {
-        break_duration = break_duration + chrono::Duration::minutes(30);
+    German.break_duration(work_duration)
+}
+
+/// A shift or break as recorded in Factorial.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shift {
+    /// The Id Factorial uses to identify the shift
+    pub id: u64,
+    /// The date and time the shift started
+    pub start: DateTime<Local>,
+    /// The date and time the shift ended, None if the shift is still open
+    pub end: Option<DateTime<Local>>,
+    /// Whether this is a break rather than a shift
+    pub is_break: bool,
+}
+impl Shift {
+    /// Get the duration of the shift, or zero if the shift is still open.
+    pub fn duration(&self) -> Duration {
+        match self.end {
+            Some(end) => end - self.start,
+            None => Duration::zero(),
+        }
+    }
+}
+
+/// Get the local date and time of `time` on `day`. A time that occurs twice because the clocks
+/// are put back is the earlier one.
+///
+/// # Errors
+/// Returns an Error if the time does not exist on that day because the clocks are put forward.
+pub fn at_time(day: NaiveDate, time: NaiveTime) -> anyhow::Result<DateTime<Local>> {
+    day.and_time(time)
+        .and_local_timezone(Local)
+        .earliest()
+        .ok_or_else(|| {
+            anyhow!(
+                "{} does not exist on {} because of the change to daylight saving time.",
+                time.format("%H:%M"),
+                day.format("%Y-%m-%d")
+            )
+        })
+}
+
+/// Get the time of day of `time` on the date of `day`.
+///
+/// # Errors
+/// Returns an Error if the time of day does not exist on that day because the clocks are put
+/// forward.
+pub fn on_day(time: DateTime<Local>, day: DateTime<Local>) -> anyhow::Result<DateTime<Local>> {
+    at_time(day.date_naive(), time.time())
+}
+
+/// Check whether `date_time` as accepted by parse_date_time already names its day, like
+/// "2024-05-02T17:45:00" or "friday 9:00", or is relative to now, like "now-5m" or "-10m".
+pub fn names_day(date_time: &str) -> bool {
+    let input = date_time.trim().to_lowercase();
+    (input.starts_with(|c: char| c.is_ascii_digit()) && input.contains('t'))
+        || input.starts_with("now")
+        || input.starts_with(['+', '-'])
+        || input
+            .split_once(char::is_whitespace)
+            .is_some_and(|(day, _)| day.starts_with(char::is_alphabetic))
+}
+
+/// Get `end` or, if it is before `start`, the same time on the day after `start`. Used for shifts
+/// that end after midnight, e.g. from 22:00 to 06:00.
+///
+/// # Errors
+/// Returns an Error if the time of `end` does not exist on the day it is moved to.
+pub fn end_after(start: DateTime<Local>, end: DateTime<Local>) -> anyhow::Result<DateTime<Local>> {
+    if end >= start {
+        return Ok(end);
     }
-    if work_duration.num_hours() >= 9 {
-        break_duration = break_duration + chrono::Duration::minutes(15);
+    let end = at_time(start.date_naive(), end.time())?;
+    if end < start {
+        at_time(start.date_naive().succ_opt().unwrap(), end.time())
+    } else {
+        Ok(end)
     }
-    break_duration
+}
+
+/// Split the time span from `start` to `end` at every midnight in between, so that every part
+/// lies on a single day. A part ending at midnight ends at 00:00 of the next day.
+pub fn split_at_midnight(
+    start: DateTime<Local>,
+    end: DateTime<Local>,
+) -> Vec<(DateTime<Local>, DateTime<Local>)> {
+    let mut parts = Vec::new();
+    let mut part_start = start;
+    while part_start < end {
+        let midnight = part_start
+            .date_naive()
+            .succ_opt()
+            .and_then(|day| {
+                day.and_time(NaiveTime::MIN)
+                    .and_local_timezone(Local)
+                    .earliest()
+            })
+            .unwrap();
+        let part_end = end.min(midnight);
+        parts.push((part_start, part_end));
+        part_start = part_end;
+    }
+    parts
+}
+
+/// Get the workday a shift or break belongs to. One that starts right where another of the
+/// `shifts` ends belongs to the same workday, so the parts of a night shift after midnight belong
+/// to the day the night shift started on. Any other one belongs to the day it starts on.
+pub fn workday_of(shift: &Shift, shifts: &[Shift]) -> NaiveDate {
+    match shifts
+        .iter()
+        .find(|s| s.end == Some(shift.start) && s.start < shift.start)
+    {
+        Some(previous) => workday_of(previous, shifts),
+        None => shift.start.date_naive(),
+    }
+}
+
+/// Format a duration as <hours>h<minutes>m, e.g. 7h30m or -0h15m.
+pub fn format_duration(duration: Duration) -> String {
+    let sign = if duration < Duration::zero() { "-" } else { "" };
+    let minutes = duration.num_minutes().abs();
+    format!("{sign}{}h{:02}m", minutes / 60, minutes % 60)
+}
+
+/// A part of a workday, either work or a break.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment {
+    /// The date and time the segment starts
+    pub start: chrono::DateTime<Local>,
+    /// The date and time the segment ends
+    pub end: chrono::DateTime<Local>,
+    /// Whether the segment is a break
+    pub is_break: bool,
 }
 
 /// A day to get work done.
 #[derive(Debug)]
 pub struct WorkDay {
-    /// The date and time to clock in
-    pub clock_in: chrono::DateTime<Local>,
-    /// The date and time to take a break
-    pub break_start: chrono::DateTime<Local>,
-    /// The date and time to end the break
-    pub break_end: chrono::DateTime<Local>,
-    /// The date and time to stop working
-    pub clock_out: chrono::DateTime<Local>,
+    /// The segments of work and breaks in chronological order, starting and ending with work
+    pub segments: Vec<Segment>,
 }
 impl WorkDay {
-    /// Get a WorkDay with times for clock in, break start, break end and clock out. Apply a random offset to the starting time and to the break time while still keeping the
-    /// break and work duratinos exact.
+    /// Get a WorkDay starting at `start` with `duration` of work in total and a break after each
+    /// given amount of work, e.g. `(4h, 30m)` takes a 30 minute break after 4 hours of work.
+    /// Breaks at the very start or end of the day are left out.
+    pub fn from_breaks(
+        start: chrono::DateTime<Local>,
+        duration: chrono::Duration,
+        breaks: &[(chrono::Duration, chrono::Duration)],
+    ) -> Self {
+        let mut breaks = breaks.to_vec();
+        breaks.sort_by_key(|(after, _)| *after);
+
+        let mut segments = Vec::new();
+        let mut time = start;
+        let mut worked = Duration::zero();
+        for (after, length) in breaks {
+            if after <= Duration::zero() || after >= duration {
+                continue;
+            }
+            segments.push(Segment {
+                start: time,
+                end: time + (after - worked),
+                is_break: false,
+            });
+            time += after - worked;
+            worked = after;
+            segments.push(Segment {
+                start: time,
+                end: time + length,
+                is_break: true,
+            });
+            time += length;
+        }
+        segments.push(Segment {
+            start: time,
+            end: time + (duration - worked),
+            is_break: false,
+        });
+
+        let mut work_day = WorkDay { segments };
+        work_day.normalize();
+        work_day
+    }
+    /// Get a WorkDay with the configured breaks. A single break in the middle of the shift is
+    /// taken if no breaks are configured. Should the configured breaks be shorter than required
+    /// by the break policy, the last break is extended.
+    pub fn plan(
+        start: chrono::DateTime<Local>,
+        duration: chrono::Duration,
+        breaks: &[PlannedBreak],
+        policy: &dyn BreakPolicy,
+    ) -> Self {
+        WorkDay::from_breaks(start, duration, &planned_breaks(duration, breaks, policy))
+    }
+    /// Get a WorkDay like [`WorkDay::plan`], but apply a random offset to the starting time and
+    /// to the time of every break while still keeping the break and work durations exact.
     pub fn randomize_shift(
         start: chrono::DateTime<Local>,
         duration: chrono::Duration,
+        breaks: &[PlannedBreak],
         max_rand_range: u16,
+        policy: &dyn BreakPolicy,
     ) -> Self {
-        let start_offset: i32 =
-            rand::thread_rng().gen_range(max_rand_range as i32 * -60..=max_rand_range as i32 * 60);
-        let break_offset: i32 =
-            rand::thread_rng().gen_range(max_rand_range as i32 * -60..=max_rand_range as i32 * 60);
+        let random_offset = || {
+            Duration::seconds(
+                rand::thread_rng()
+                    .gen_range(max_rand_range as i64 * -60..=max_rand_range as i64 * 60),
+            )
+        };
+        let clock_in = start + random_offset();
+        let breaks: Vec<(Duration, Duration)> = planned_breaks(duration, breaks, policy)
+            .into_iter()
+            .map(|(after, length)| {
+                let after = (after + random_offset())
+                    .max(Duration::minutes(1))
+                    .min(duration - Duration::minutes(1));
+                (after, length)
+            })
+            .collect();
+        WorkDay::from_breaks(clock_in, duration, &breaks)
+    }
+    /// Get a WorkDay with a single break in the middle of the shift. The length of the break is
+    /// given by the break policy.
+    pub fn standard_shift(
+        start: chrono::DateTime<Local>,
+        duration: chrono::Duration,
+        policy: &dyn BreakPolicy,
+    ) -> Self {
+        WorkDay::plan(start, duration, &[], policy)
+    }
+    /// The date and time to clock in
+    pub fn clock_in(&self) -> chrono::DateTime<Local> {
+        self.segments.first().unwrap().start
+    }
+    /// The date and time to stop working
+    pub fn clock_out(&self) -> chrono::DateTime<Local> {
+        self.segments.last().unwrap().end
+    }
+    /// Get the segments as shifts and breaks that have not been booked yet.
+    pub fn shifts(&self) -> Vec<Shift> {
+        self.segments
+            .iter()
+            .map(|s| Shift {
+                id: 0,
+                start: s.start,
+                end: Some(s.end),
+                is_break: s.is_break,
+            })
+            .collect()
+    }
+    /// Get all breaks of the day.
+    pub fn breaks(&self) -> impl Iterator<Item = &Segment> {
+        self.segments.iter().filter(|s| s.is_break)
+    }
+    /// Move the longest break as close as possible into the time span from `earliest` to
+    /// `latest` without moving it past the segments of work around it. The times for clock in
+    /// and clock out stay the same.
+    pub fn move_break_into(&mut self, earliest: DateTime<Local>, latest: DateTime<Local>) {
+        let i = match self.longest_break() {
+            Some(i) => i,
+            None => return,
+        };
+        let break_duration = self.segments[i].end - self.segments[i].start;
+        // Breaks are always surrounded by work
+        let lower = self.segments[i - 1].start;
+        let upper = self.segments[i + 1].end;
+        let break_start = self.segments[i]
+            .start
+            .min(latest - break_duration)
+            .max(earliest)
+            .min(upper - break_duration)
+            .max(lower);
+        self.segments[i - 1].end = break_start;
+        self.segments[i].start = break_start;
+        self.segments[i].end = break_start + break_duration;
+        self.segments[i + 1].start = break_start + break_duration;
+        self.normalize();
+    }
+    /// Place the longest break inside the time span from `earliest` to `latest` if the shift
+    /// covers it and the work before the break stays within the limit of the break policy.
+    /// Otherwise the break is taken at the latest point the break policy allows. The times for
+    /// clock in and clock out stay the same.
+    pub fn place_break_in_window(
+        &mut self,
+        earliest: DateTime<Local>,
+        latest: DateTime<Local>,
+        policy: &dyn BreakPolicy,
+    ) {
+        let i = match self.longest_break() {
+            Some(i) => i,
+            None => return,
+        };
+        let break_duration = self.segments[i].end - self.segments[i].start;
+        let lower = self.segments[i - 1].start;
+        let upper = self.segments[i + 1].end;
+        let legal_latest = match policy.max_work_without_break() {
+            Some(max_work) => lower + max_work,
+            None => upper - break_duration,
+        };
 
-        let clock_in = start
-            .checked_add_signed(Duration::seconds(start_offset.into()))
-            .unwrap();
-        let first_shift_len = duration
-            .checked_div(2)
-            .unwrap()
-            .checked_add(&Duration::seconds(break_offset.into()))
-            .unwrap();
-        let second_shift_len = duration.checked_sub(&first_shift_len).unwrap();
-        let break_start = clock_in.checked_add_signed(first_shift_len).unwrap();
-        let break_end = break_start
-            .checked_add_signed(get_break_duration(duration))
-            .unwrap();
-        let clock_out = break_end.checked_add_signed(second_shift_len).unwrap();
+        let window_start = earliest.max(lower);
+        let window_end = latest.min(upper);
+        if window_start + break_duration <= window_end && window_start <= legal_latest {
+            self.move_break_into(window_start, window_end.min(legal_latest + break_duration));
+        } else {
+            let break_start = legal_latest.min(upper - break_duration);
+            self.move_break_into(break_start, break_start + break_duration);
+        }
+    }
+    /// Get the index of the longest break.
+    fn longest_break(&self) -> Option<usize> {
+        self.segments
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.is_break)
+            .max_by_key(|(_, s)| s.end - s.start)
+            .map(|(i, _)| i)
+    }
+    /// Remove empty segments and merge consecutive segments of the same kind.
+    fn normalize(&mut self) {
+        let mut segments: Vec<Segment> = Vec::new();
+        for segment in self.segments.iter().filter(|s| s.start < s.end) {
+            match segments.last_mut() {
+                Some(last) if last.is_break == segment.is_break => last.end = segment.end,
+                _ => segments.push(*segment),
+            }
+        }
+        self.segments = segments;
+    }
+}
 
-        WorkDay {
-            clock_in,
-            break_start,
-            break_end,
-            clock_out,
+/// Get the amount of work before each break and the length of the break. Without configured
+/// breaks, a single break in the middle of the shift is taken. The last break is extended if the
+/// breaks are shorter than required by the break policy.
+fn planned_breaks(
+    duration: chrono::Duration,
+    breaks: &[PlannedBreak],
+    policy: &dyn BreakPolicy,
+) -> Vec<(Duration, Duration)> {
+    let mut planned: Vec<(Duration, Duration)> = breaks
+        .iter()
+        .map(|b| {
+            (
+                Duration::seconds((b.after_hours * 60.0 * 60.0) as i64),
+                Duration::minutes(b.minutes.into()),
+            )
+        })
+        .filter(|(after, _)| *after > Duration::zero() && *after < duration)
+        .collect();
+    let required = policy.break_duration(duration);
+    if planned.is_empty() {
+        if required > Duration::zero() {
+            planned.push((duration / 2, required));
         }
+        return planned;
     }
-    /// Get a WorkDay with times for clock in, break start, break end and clock out.
-    pub fn standard_shift(start: chrono::DateTime<Local>, duration: chrono::Duration) -> Self {
-        let clock_in = start;
-        let break_start = start
-            .checked_add_signed(duration.checked_div(2).unwrap())
-            .unwrap();
-        let break_end = break_start
-            .checked_add_signed(get_break_duration(duration))
-            .unwrap();
-        let clock_out = break_end
-            .checked_add_signed(duration.checked_div(2).unwrap())
-            .unwrap();
-        WorkDay {
-            clock_in,
-            break_start,
-            break_end,
-            clock_out,
+    planned.sort_by_key(|(after, _)| *after);
+    let total = planned
+        .iter()
+        .fold(Duration::zero(), |total, (_, length)| total + *length);
+    if total < required {
+        planned.last_mut().unwrap().1 += required - total;
+    }
+    planned
+}
+
+/// Plan the rest of a day of which the `recorded` shifts and breaks exist already, so that
+/// `duration` is worked in total with the breaks the break policy requires. Gaps between the
+/// recorded shifts count as breaks and work recorded right before resuming counts towards the
+/// maximum time of work without a break. The recorded shifts and breaks stay untouched, except for
+/// an open shift or break which is continued or closed at the start of the first segment. Returns
+/// the segments to add.
+pub fn complete_day(
+    recorded: &[Shift],
+    duration: Duration,
+    policy: &dyn BreakPolicy,
+) -> Vec<Segment> {
+    let first_start = match recorded.iter().map(|s| s.start).min() {
+        Some(start) => start,
+        None => return Vec::new(),
+    };
+    let open = recorded.iter().find(|s| s.end.is_none());
+    let resume = match open {
+        Some(open) => open.start,
+        None => recorded.iter().filter_map(|s| s.end).max().unwrap(),
+    };
+    let worked = recorded
+        .iter()
+        .filter(|s| !s.is_break)
+        .fold(Duration::zero(), |worked, s| worked + s.duration());
+    let remaining = (duration - worked).max(Duration::zero());
+    let taken_break = (resume - first_start) - worked;
+    let missing_break = (policy.break_duration(duration) - taken_break).max(Duration::zero());
+    if open.is_none() && remaining == Duration::zero() {
+        return Vec::new();
+    }
+
+    let mut segments = Vec::new();
+    let mut time = resume;
+    let mut work_left = remaining;
+    if let Some(Shift { is_break: true, .. }) = open {
+        // Continue the open break for as long as a break is still required
+        segments.push(Segment {
+            start: time,
+            end: time + missing_break,
+            is_break: true,
+        });
+        time += missing_break;
+    } else if missing_break > Duration::zero() {
+        let mut before_break = remaining / 2;
+        if let Some(max_work) = policy.max_work_without_break() {
+            let max_left =
+                (max_work - continuous_work_until(recorded, resume)).max(Duration::zero());
+            before_break = before_break.min(max_left);
         }
+        segments.push(Segment {
+            start: time,
+            end: time + before_break,
+            is_break: false,
+        });
+        segments.push(Segment {
+            start: time + before_break,
+            end: time + before_break + missing_break,
+            is_break: true,
+        });
+        time += before_break + missing_break;
+        work_left -= before_break;
+    }
+    segments.push(Segment {
+        start: time,
+        end: time + work_left,
+        is_break: false,
+    });
+    segments.retain(|s| s.end > s.start);
+    // An open shift or break has to be closed even if there is nothing left to do
+    if segments.is_empty() {
+        segments.push(Segment {
+            start: resume,
+            end: resume,
+            is_break: false,
+        });
+    }
+    segments
+}
+
+/// Get the time worked without a break right before `time`. Shifts that follow each other without
+/// a gap count as one.
+fn continuous_work_until(recorded: &[Shift], time: DateTime<Local>) -> Duration {
+    let mut worked = Duration::zero();
+    let mut until = time;
+    while let Some(shift) = recorded
+        .iter()
+        .find(|s| !s.is_break && s.end == Some(until) && s.start < until)
+    {
+        worked += shift.duration();
+        until = shift.start;
     }
+    worked
 }
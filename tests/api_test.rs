@@ -3,7 +3,12 @@ use std::sync::Mutex;
 use chrono::{DateTime, Datelike, Days, Local, Timelike, Weekday};
 use once_cell::sync::Lazy;
 use serial_test::serial;
-use tracktorial::{api::FactorialApi, config::Configuration, login::Credential};
+use tracktorial::{
+    api::{FactorialApi, LeaveType, ShiftData},
+    config::Configuration,
+    login::Credential,
+    time::{parse_date_time, split_at_midnight, LeaveKind},
+};
 
 static API_MUTEX: Lazy<Mutex<FactorialApi>> = Lazy::new(|| {
     let api = FactorialApi::get_api().unwrap();
@@ -152,6 +157,31 @@ fn create_shift() {
     assert_eq!(true, result.is_ok());
 }
 
+#[serial]
+#[test]
+fn create_shift_across_midnight() {
+    let start = parse_date_time(&format!(
+        "{}T22:00:00",
+        get_next_sunday().format("%Y-%m-%d")
+    ))
+    .unwrap();
+    let end = start + chrono::Duration::hours(8);
+    let api = API_MUTEX.lock().unwrap();
+    let result = api.make_shift(start, end);
+    let shifts = api.get_shifts(start, end).unwrap();
+    api.delete_all_shifts(start).unwrap();
+    api.delete_all_shifts(end).unwrap();
+    assert_eq!(2, result.unwrap().len());
+    let parts: Vec<(DateTime<Local>, Option<DateTime<Local>>)> = split_at_midnight(start, end)
+        .into_iter()
+        .map(|(s, e)| (s, Some(e)))
+        .collect();
+    assert_eq!(
+        parts,
+        shifts.iter().map(|s| (s.start, s.end)).collect::<Vec<_>>()
+    );
+}
+
 #[serial]
 #[test]
 fn create_break() {
@@ -174,3 +204,68 @@ fn get_next_sunday() -> DateTime<Local> {
     }
     today
 }
+
+fn leave_types() -> Vec<LeaveType> {
+    vec![
+        LeaveType {
+            id: 1,
+            name: "Urlaub".to_string(),
+            kind: LeaveKind::from_name("vacation"),
+        },
+        LeaveType {
+            id: 2,
+            name: "Krankheit".to_string(),
+            kind: LeaveKind::from_name("sick_leave"),
+        },
+        LeaveType {
+            id: 3,
+            name: "Doctor's appointment".to_string(),
+            kind: LeaveKind::from_name("Doctor's appointment"),
+        },
+    ]
+}
+
+#[test]
+fn find_leave_type_by_kind() {
+    let leave_types = leave_types();
+    assert_eq!(1, LeaveType::find(&leave_types, "vacation").unwrap().id);
+    assert_eq!(2, LeaveType::find(&leave_types, "sick-leave").unwrap().id);
+}
+
+#[test]
+fn find_leave_type_by_name() {
+    let leave_types = leave_types();
+    assert_eq!(1, LeaveType::find(&leave_types, "urlaub").unwrap().id);
+    assert_eq!(
+        3,
+        LeaveType::find(&leave_types, "Doctor's Appointment")
+            .unwrap()
+            .id
+    );
+}
+
+#[test]
+fn find_unknown_leave_type() {
+    assert!(LeaveType::find(&leave_types(), "training").is_none());
+}
+
+#[test]
+fn night_shift_parts_are_booked_on_their_own_day() {
+    let start = parse_date_time("2024-05-02T22:00:00").unwrap();
+    let end = parse_date_time("2024-05-03T06:00:00").unwrap();
+    let parts: Vec<serde_json::Value> = split_at_midnight(start, end)
+        .into_iter()
+        .map(|(start, end)| {
+            serde_json::to_value(ShiftData::new(start, end, "office", 7, false)).unwrap()
+        })
+        .collect();
+    assert_eq!(2, parts.len());
+    assert_eq!("22:00", parts[0]["clock_in"]);
+    assert_eq!("23:59:59", parts[0]["clock_out"]);
+    assert_eq!("2024-05-02", parts[0]["date"]);
+    assert_eq!(2, parts[0]["day"]);
+    assert_eq!("00:00", parts[1]["clock_in"]);
+    assert_eq!("06:00", parts[1]["clock_out"]);
+    assert_eq!("2024-05-03", parts[1]["date"]);
+    assert_eq!(3, parts[1]["day"]);
+}
@@ -0,0 +1,122 @@
+use chrono::{Duration, NaiveDate};
+use tracktorial::{
+    balance::{daily_balances, monthly_balances, weekly_balances, Balance},
+    break_policy::German,
+    time::{parse_date, parse_date_time, FreeDay, FreeDayReason, HalfDay, LeaveKind, Shift},
+};
+
+fn shift(id: u64, start: &str, end: &str, is_break: bool) -> Shift {
+    Shift {
+        id,
+        start: parse_date_time(start).unwrap(),
+        end: Some(parse_date_time(end).unwrap()),
+        is_break,
+    }
+}
+
+#[test]
+fn surplus_is_worked_minus_expected() {
+    let balance = Balance {
+        expected: Duration::hours(8),
+        worked: Duration::hours(7),
+    };
+    assert_eq!(Duration::hours(-1), balance.surplus());
+}
+
+#[test]
+fn breaks_do_not_count_as_worked_time() {
+    let shifts = vec![
+        shift(1, "2024-05-02T08:00:00", "2024-05-02T12:00:00", false),
+        shift(2, "2024-05-02T12:00:00", "2024-05-02T12:30:00", true),
+        shift(3, "2024-05-02T12:30:00", "2024-05-02T17:00:00", false),
+    ];
+    let day = parse_date("2024-05-02").unwrap();
+    let balances = daily_balances(day, day, |_| Duration::hours(8), &[], &shifts, &German);
+    assert_eq!(1, balances.len());
+    assert_eq!(Duration::minutes(8 * 60 + 30), balances[0].1.worked);
+    assert_eq!(Duration::minutes(30), balances[0].1.surplus());
+}
+
+#[test]
+fn night_shift_counts_for_the_day_it_started_on() {
+    let shifts = vec![
+        shift(1, "2024-05-01T22:00:00", "2024-05-02T00:00:00", false),
+        shift(2, "2024-05-02T00:00:00", "2024-05-02T02:00:00", false),
+        shift(3, "2024-05-02T02:00:00", "2024-05-02T02:30:00", true),
+        shift(4, "2024-05-02T02:30:00", "2024-05-02T06:30:00", false),
+        shift(5, "2024-05-02T22:00:00", "2024-05-03T00:00:00", false),
+        shift(6, "2024-05-03T00:00:00", "2024-05-03T06:30:00", false),
+    ];
+    let from = parse_date("2024-05-02").unwrap();
+    let to = parse_date("2024-05-03").unwrap();
+    let balances = daily_balances(from, to, |_| Duration::hours(8), &[], &shifts, &German);
+    assert_eq!(2, balances.len());
+    assert_eq!(
+        Duration::hours(8) + Duration::minutes(30),
+        balances[0].1.worked
+    );
+    assert_eq!(Duration::zero(), balances[1].1.worked);
+}
+
+#[test]
+fn free_days_reduce_expected_time() {
+    let free_days = vec![
+        FreeDay::from(parse_date("2024-05-04").unwrap()),
+        FreeDay {
+            day: parse_date("2024-05-03").unwrap(),
+            half: HalfDay::EndOfDay,
+            hours: None,
+            reason: FreeDayReason::Leave {
+                kind: LeaveKind::Vacation,
+                approved: true,
+            },
+        },
+        FreeDay {
+            day: parse_date("2024-05-02").unwrap(),
+            half: HalfDay::WholeDay,
+            hours: None,
+            reason: FreeDayReason::Leave {
+                kind: LeaveKind::Vacation,
+                approved: false,
+            },
+        },
+    ];
+    let balances = daily_balances(
+        parse_date("2024-05-02").unwrap(),
+        parse_date("2024-05-04").unwrap(),
+        |_| Duration::hours(8),
+        &free_days,
+        &[],
+        &German,
+    );
+    let expected: Vec<Duration> = balances.iter().map(|(_, b)| b.expected).collect();
+    assert_eq!(
+        vec![Duration::hours(8), Duration::hours(4), Duration::zero()],
+        expected
+    );
+}
+
+#[test]
+fn balances_are_grouped_by_week_and_month() {
+    let balances = daily_balances(
+        parse_date("2024-04-28").unwrap(),
+        parse_date("2024-05-06").unwrap(),
+        |_| Duration::hours(1),
+        &[],
+        &[],
+        &German,
+    );
+    let weeks = weekly_balances(&balances);
+    assert_eq!(
+        vec![(2024, 17), (2024, 18), (2024, 19)],
+        weeks.iter().map(|(w, _)| *w).collect::<Vec<_>>()
+    );
+    assert_eq!(Duration::hours(7), weeks[1].1.expected);
+    let months = monthly_balances(&balances);
+    assert_eq!(2, months.len());
+    assert_eq!(Duration::hours(3), months[0].1.expected);
+    assert_eq!(
+        NaiveDate::from_ymd_opt(2024, 5, 6).unwrap(),
+        balances.last().unwrap().0
+    );
+}
@@ -0,0 +1,99 @@
+use chrono::Duration;
+use tracktorial::break_policy::{
+    Austrian, BreakPolicy, BreakPolicyConfig, BreakThreshold, Spanish, Swiss,
+};
+
+#[test]
+fn austrian_break_is_thirty_min_from_six_h() {
+    assert_eq!(
+        Duration::zero(),
+        Austrian.break_duration(Duration::minutes(359))
+    );
+    assert_eq!(
+        Duration::minutes(30),
+        Austrian.break_duration(Duration::hours(10))
+    );
+}
+
+#[test]
+fn spanish_break_is_fifteen_min_from_six_h() {
+    assert_eq!(
+        Duration::minutes(15),
+        Spanish.break_duration(Duration::hours(8))
+    );
+}
+
+#[test]
+fn swiss_break_grows_with_work_duration() {
+    assert_eq!(
+        Duration::minutes(15),
+        Swiss.break_duration(Duration::minutes(330))
+    );
+    assert_eq!(
+        Duration::minutes(30),
+        Swiss.break_duration(Duration::hours(8))
+    );
+    assert_eq!(Duration::hours(1), Swiss.break_duration(Duration::hours(9)));
+}
+
+#[test]
+fn custom_policy_from_config() {
+    let policy: BreakPolicyConfig = serde_json::from_str(
+        r#"{ "custom": [ { "after_hours": 4.0, "minutes": 20 }, { "after_hours": 8.0, "minutes": 40 } ] }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        BreakPolicyConfig::Custom(vec![
+            BreakThreshold {
+                after_hours: 4.0,
+                minutes: 20
+            },
+            BreakThreshold {
+                after_hours: 8.0,
+                minutes: 40
+            },
+        ]),
+        policy
+    );
+    assert_eq!(
+        Duration::zero(),
+        policy.break_duration(Duration::minutes(239))
+    );
+    assert_eq!(
+        Duration::minutes(20),
+        policy.break_duration(Duration::hours(6))
+    );
+    assert_eq!(
+        Duration::minutes(40),
+        policy.break_duration(Duration::hours(8))
+    );
+}
+
+#[test]
+fn country_policy_from_config() {
+    let policy: BreakPolicyConfig = serde_json::from_str(r#""austria""#).unwrap();
+    assert_eq!(BreakPolicyConfig::Austria, policy);
+}
+
+#[test]
+fn max_work_without_break_is_lowest_threshold() {
+    assert_eq!(Some(Duration::minutes(330)), Swiss.max_work_without_break());
+    assert_eq!(
+        Some(Duration::hours(4)),
+        BreakPolicyConfig::Custom(vec![
+            BreakThreshold {
+                after_hours: 8.0,
+                minutes: 40
+            },
+            BreakThreshold {
+                after_hours: 4.0,
+                minutes: 20
+            },
+        ])
+        .max_work_without_break()
+    );
+    assert_eq!(
+        None,
+        BreakPolicyConfig::Custom(vec![]).max_work_without_break()
+    );
+}
@@ -0,0 +1,93 @@
+use std::cell::Cell;
+
+use chrono::Duration;
+use serde_json::json;
+use tracktorial::cache::Cache;
+
+#[test]
+fn value_is_only_fetched_once() {
+    let cache = Cache::in_memory();
+    let fetched = Cell::new(0);
+    let fetch = || {
+        fetched.set(fetched.get() + 1);
+        Ok(json!([{ "id": 1 }]))
+    };
+    assert_eq!(
+        json!([{ "id": 1 }]),
+        cache.get_or_fetch("periods", fetch).unwrap()
+    );
+    assert_eq!(
+        json!([{ "id": 1 }]),
+        cache.get_or_fetch("periods", fetch).unwrap()
+    );
+    assert_eq!(1, fetched.get());
+
+    cache.invalidate("periods");
+    cache.get_or_fetch("periods", fetch).unwrap();
+    assert_eq!(2, fetched.get());
+}
+
+#[test]
+fn failed_fetch_is_not_cached() {
+    let cache = Cache::in_memory();
+    assert!(cache
+        .get_or_fetch("holidays", || Err(anyhow::anyhow!("offline")))
+        .is_err());
+    assert_eq!(
+        json!([]),
+        cache.get_or_fetch("holidays", || Ok(json!([]))).unwrap()
+    );
+}
+
+#[test]
+fn persisted_values_are_loaded_until_they_expire() {
+    let path = std::env::temp_dir().join(format!("tracktorial-cache-{}.json", std::process::id()));
+    let cache = Cache::persisted(path.clone(), Duration::minutes(10));
+    cache
+        .get_or_fetch("holidays", || Ok(json!(["2024-12-25"])))
+        .unwrap();
+
+    let reloaded = Cache::persisted(path.clone(), Duration::minutes(10));
+    assert_eq!(
+        json!(["2024-12-25"]),
+        reloaded
+            .get_or_fetch("holidays", || Err(anyhow::anyhow!("should be cached")))
+            .unwrap()
+    );
+
+    let expired = Cache::persisted(path.clone(), Duration::zero());
+    assert!(expired
+        .get_or_fetch("holidays", || Err(anyhow::anyhow!("expired")))
+        .is_err());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn in_memory_values_are_not_persisted() {
+    let path = std::env::temp_dir().join(format!(
+        "tracktorial-cache-memory-{}.json",
+        std::process::id()
+    ));
+    let cache = Cache::persisted(path.clone(), Duration::minutes(10));
+    cache
+        .get_or_fetch_in_memory("periods", || Ok(json!([{ "state": "open" }])))
+        .unwrap();
+    assert_eq!(
+        json!([{ "state": "open" }]),
+        cache
+            .get_or_fetch_in_memory("periods", || Err(anyhow::anyhow!("should be cached")))
+            .unwrap()
+    );
+    cache
+        .get_or_fetch("holidays", || Ok(json!(["2024-12-25"])))
+        .unwrap();
+
+    let reloaded = Cache::persisted(path.clone(), Duration::minutes(10));
+    assert_eq!(
+        json!([{ "state": "approved" }]),
+        reloaded
+            .get_or_fetch_in_memory("periods", || Ok(json!([{ "state": "approved" }])))
+            .unwrap()
+    );
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,223 @@
+use chrono::{Duration, NaiveDate};
+use tracktorial::{
+    break_policy::German,
+    compliance::{check, Violation},
+    time::{parse_date, parse_date_time, FreeDay, FreeDayReason, HalfDay, LeaveKind, Shift},
+};
+
+fn shift(start: &str, end: &str, is_break: bool) -> Shift {
+    Shift {
+        id: 0,
+        start: parse_date_time(start).unwrap(),
+        end: Some(parse_date_time(end).unwrap()),
+        is_break,
+    }
+}
+
+fn day(date: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+}
+
+#[test]
+fn regular_day_has_no_violations() {
+    let shifts = vec![
+        shift("2024-05-02T08:00:00", "2024-05-02T12:00:00", false),
+        shift("2024-05-02T12:00:00", "2024-05-02T12:30:00", true),
+        shift("2024-05-02T12:30:00", "2024-05-02T16:30:00", false),
+    ];
+    assert!(check(&shifts, &[], &German).is_empty());
+}
+
+#[test]
+fn more_than_ten_hours_a_day() {
+    let shifts = vec![
+        shift("2024-05-02T06:00:00", "2024-05-02T12:00:00", false),
+        shift("2024-05-02T12:45:00", "2024-05-02T17:45:00", false),
+    ];
+    assert_eq!(
+        vec![Violation::MaxDailyHours {
+            day: day("2024-05-02"),
+            worked: Duration::hours(11),
+        }],
+        check(&shifts, &[], &German)
+    );
+}
+
+#[test]
+fn less_than_eleven_hours_of_rest() {
+    let shifts = vec![
+        shift("2024-05-02T14:00:00", "2024-05-02T19:00:00", false),
+        shift("2024-05-03T05:00:00", "2024-05-03T10:00:00", false),
+    ];
+    assert_eq!(
+        vec![Violation::MinimumRest {
+            day: day("2024-05-03"),
+            rest: Duration::hours(10),
+        }],
+        check(&shifts, &[], &German)
+    );
+}
+
+#[test]
+fn gaps_between_shifts_count_as_break() {
+    let shifts = vec![
+        shift("2024-05-02T08:00:00", "2024-05-02T12:00:00", false),
+        shift("2024-05-02T12:15:00", "2024-05-02T16:15:00", false),
+    ];
+    assert_eq!(
+        vec![Violation::BreakTooShort {
+            day: day("2024-05-02"),
+            taken: Duration::minutes(15),
+            required: Duration::minutes(30),
+            worked: Duration::hours(8),
+        }],
+        check(&shifts, &[], &German)
+    );
+}
+
+#[test]
+fn work_on_sundays_and_holidays() {
+    let shifts = vec![
+        shift("2024-05-01T08:00:00", "2024-05-01T12:00:00", false),
+        shift("2024-05-05T08:00:00", "2024-05-05T12:00:00", false),
+    ];
+    let free_days = vec![FreeDay {
+        day: parse_date("2024-05-01").unwrap(),
+        half: HalfDay::WholeDay,
+        hours: None,
+        reason: FreeDayReason::Holiday("Labour Day".to_string()),
+    }];
+    let violations = check(&shifts, &free_days, &German);
+    assert_eq!(
+        vec![
+            Violation::FreeDay {
+                day: day("2024-05-01"),
+                reason: FreeDayReason::Holiday("Labour Day".to_string()),
+            },
+            Violation::Sunday {
+                day: day("2024-05-05")
+            },
+        ],
+        violations
+    );
+    assert_eq!(
+        "Working on a free day: Holiday (Labour Day)",
+        violations[0].to_string()
+    );
+}
+
+#[test]
+fn work_on_approved_leave_but_not_on_half_or_pending_days() {
+    let shifts = vec![
+        shift("2024-05-02T08:00:00", "2024-05-02T12:00:00", false),
+        shift("2024-05-03T08:00:00", "2024-05-03T12:00:00", false),
+        shift("2024-05-06T08:00:00", "2024-05-06T12:00:00", false),
+    ];
+    let free_days = vec![
+        FreeDay {
+            day: parse_date("2024-05-02").unwrap(),
+            half: HalfDay::WholeDay,
+            hours: None,
+            reason: FreeDayReason::Leave {
+                kind: LeaveKind::Vacation,
+                approved: true,
+            },
+        },
+        FreeDay {
+            day: parse_date("2024-05-03").unwrap(),
+            half: HalfDay::WholeDay,
+            hours: None,
+            reason: FreeDayReason::Leave {
+                kind: LeaveKind::Vacation,
+                approved: false,
+            },
+        },
+        FreeDay {
+            day: parse_date("2024-05-06").unwrap(),
+            half: HalfDay::EndOfDay,
+            hours: None,
+            reason: FreeDayReason::Holiday("Half holiday".to_string()),
+        },
+    ];
+    assert_eq!(
+        vec![Violation::FreeDay {
+            day: day("2024-05-02"),
+            reason: FreeDayReason::Leave {
+                kind: LeaveKind::Vacation,
+                approved: true,
+            },
+        }],
+        check(&shifts, &free_days, &German)
+    );
+}
+
+#[test]
+fn too_long_without_a_break() {
+    let shifts = vec![
+        shift("2024-05-02T08:00:00", "2024-05-02T12:00:00", false),
+        shift("2024-05-02T12:00:00", "2024-05-02T14:30:00", false),
+        shift("2024-05-02T14:30:00", "2024-05-02T15:15:00", true),
+        shift("2024-05-02T15:15:00", "2024-05-02T16:15:00", false),
+    ];
+    let violations = check(&shifts, &[], &German);
+    assert_eq!(
+        vec![Violation::ContinuousWork {
+            day: day("2024-05-02"),
+            worked: Duration::minutes(390),
+            max: Duration::hours(6),
+        }],
+        violations
+    );
+    assert_eq!(
+        "Worked 6h30m without a break, but at most 6h00m are allowed",
+        violations[0].to_string()
+    );
+}
+
+#[test]
+fn open_shift_on_sunday() {
+    let shifts = vec![Shift {
+        id: 0,
+        start: parse_date_time("2024-05-05T08:00:00").unwrap(),
+        end: None,
+        is_break: false,
+    }];
+    assert_eq!(
+        vec![Violation::Sunday {
+            day: day("2024-05-05")
+        }],
+        check(&shifts, &[], &German)
+    );
+}
+
+#[test]
+fn sunday_is_not_reported_as_non_working_day() {
+    let shifts = vec![shift("2024-05-05T08:00:00", "2024-05-05T12:00:00", false)];
+    let free_days = vec![FreeDay {
+        day: parse_date("2024-05-05").unwrap(),
+        half: HalfDay::WholeDay,
+        hours: None,
+        reason: FreeDayReason::NonWorkingDay,
+    }];
+    assert_eq!(
+        vec![Violation::Sunday {
+            day: day("2024-05-05")
+        }],
+        check(&shifts, &free_days, &German)
+    );
+}
+
+#[test]
+fn night_shifts_split_at_midnight_count_as_one() {
+    let shifts = vec![
+        shift("2024-05-02T22:00:00", "2024-05-03T00:00:00", false),
+        shift("2024-05-03T00:00:00", "2024-05-03T02:00:00", false),
+        shift("2024-05-03T02:00:00", "2024-05-03T02:30:00", true),
+        shift("2024-05-03T02:30:00", "2024-05-03T06:30:00", false),
+        shift("2024-05-03T22:00:00", "2024-05-04T00:00:00", false),
+        shift("2024-05-04T00:00:00", "2024-05-04T02:00:00", false),
+        shift("2024-05-04T02:00:00", "2024-05-04T02:30:00", true),
+        shift("2024-05-04T02:30:00", "2024-05-04T06:30:00", false),
+    ];
+    assert_eq!(Vec::<Violation>::new(), check(&shifts, &[], &German));
+}
@@ -1,4 +1,11 @@
-use tracktorial::{api::FactorialApi, config::Configuration, login::Credential};
+use std::collections::BTreeMap;
+
+use chrono::{Duration, NaiveDate};
+use tracktorial::{
+    api::FactorialApi,
+    config::{Configuration, ContractVersion, DaySchedule, HoursFrequency},
+    login::Credential,
+};
 
 #[test]
 fn can_retrieve_config_file() {
@@ -15,3 +22,177 @@ fn config_with_only_email_gets_repopulated_on_login() {
     FactorialApi::new(cred, &mut minimal_config).unwrap();
     assert_eq!(my_config, minimal_config);
 }
+
+fn contract(working_hours: f32, days: &[&str]) -> ContractVersion {
+    ContractVersion {
+        effective_on: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+        working_hours,
+        working_hours_frequency: HoursFrequency::Weekly,
+        working_week_days: days.iter().map(|d| d.to_string()).collect(),
+    }
+}
+
+#[test]
+fn contracts_are_retrieved_once_a_day() {
+    let today = NaiveDate::from_ymd_opt(2024, 5, 2).unwrap();
+    let mut config = Configuration::default();
+    config.contracts_checked_on = Some(today);
+    assert!(config.contracts_outdated(today));
+    config.contracts = vec![contract(40.0, &["monday"])];
+    assert!(!config.contracts_outdated(today));
+    assert!(config.contracts_outdated(today.succ_opt().unwrap()));
+    config.contracts_checked_on = None;
+    assert!(config.contracts_outdated(today));
+}
+
+#[test]
+fn config_follows_new_contract_version() {
+    let previous = contract(
+        40.0,
+        &["monday", "tuesday", "wednesday", "thursday", "friday"],
+    );
+    let current = contract(32.0, &["monday", "tuesday", "wednesday", "thursday"]);
+    let mut config = Configuration::default();
+    config.working_hours = previous.weekly_hours();
+    config.shift_duration = previous.daily_hours();
+    config.working_week_days = previous.working_week_days.clone();
+    config.follow_contract(&previous, &current);
+    assert_eq!(32.0, config.working_hours);
+    assert_eq!(8.0, config.shift_duration);
+    assert_eq!(current.working_week_days, config.working_week_days);
+}
+
+#[test]
+fn manual_settings_survive_new_contract_version() {
+    let previous = contract(
+        40.0,
+        &["monday", "tuesday", "wednesday", "thursday", "friday"],
+    );
+    let current = contract(32.0, &["monday", "tuesday", "wednesday", "thursday"]);
+    let mut config = Configuration::default();
+    config.working_hours = 30.0;
+    config.shift_duration = 6.0;
+    config.working_week_days = vec!["monday".to_string()];
+    config.follow_contract(&previous, &current);
+    assert_eq!(30.0, config.working_hours);
+    assert_eq!(6.0, config.shift_duration);
+    assert_eq!(vec!["monday".to_string()], config.working_week_days);
+}
+
+#[test]
+fn contract_version_applies_until_the_next_one() {
+    let mut config = Configuration::default();
+    config.working_week_days = vec!["monday".to_string(), "tuesday".to_string()];
+    config.shift_duration = 6.4;
+    config.contracts = vec![
+        ContractVersion {
+            effective_on: NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+            working_hours: 40.0,
+            working_hours_frequency: HoursFrequency::Weekly,
+            working_week_days: ["monday", "tuesday", "wednesday", "thursday", "friday"]
+                .iter()
+                .map(|d| d.to_string())
+                .collect(),
+        },
+        ContractVersion {
+            effective_on: NaiveDate::from_ymd_opt(2024, 7, 1).unwrap(),
+            working_hours: 32.0,
+            working_hours_frequency: HoursFrequency::Weekly,
+            working_week_days: ["monday", "tuesday", "wednesday", "thursday"]
+                .iter()
+                .map(|d| d.to_string())
+                .collect(),
+        },
+    ];
+    // Friday before and after the change
+    let before = NaiveDate::from_ymd_opt(2024, 6, 28).unwrap();
+    let after = NaiveDate::from_ymd_opt(2024, 7, 5).unwrap();
+    assert_eq!(Duration::hours(8), config.daily_target(before));
+    assert_eq!(Duration::minutes(384), config.daily_target(after));
+    assert!(config.is_working_day(before));
+    assert!(!config.is_working_day(after));
+    // The current contract version uses the configured working week days
+    let wednesday = NaiveDate::from_ymd_opt(2024, 7, 3).unwrap();
+    assert!(!config.is_working_day(wednesday));
+    assert!(config.is_working_day(NaiveDate::from_ymd_opt(2024, 6, 26).unwrap()));
+    // No contract applies yet, fall back to working_week_days
+    let monday = NaiveDate::from_ymd_opt(2022, 12, 26).unwrap();
+    assert!(config.is_working_day(monday));
+    assert!(!config.is_working_day(monday.succ_opt().unwrap().succ_opt().unwrap()));
+}
+
+#[test]
+fn fractional_weekly_hours_are_parsed() {
+    let contract = serde_json::json!({
+        "effective_on": "2024-01-01",
+        "working_hours": 3750,
+        "working_hours_frequency": "weekly",
+        "working_week_days": "monday,tuesday,wednesday,thursday,friday"
+    });
+    let contract = ContractVersion::from_factorial(&contract).unwrap();
+    assert_eq!(37.5, contract.working_hours);
+    assert_eq!(7.5, contract.daily_hours());
+}
+
+#[test]
+fn single_digit_weekly_hours_are_parsed() {
+    let contract = serde_json::json!({
+        "working_hours": 800,
+        "working_week_days": "monday,tuesday"
+    });
+    let contract = ContractVersion::from_factorial(&contract).unwrap();
+    assert_eq!(HoursFrequency::Weekly, contract.working_hours_frequency);
+    assert_eq!(4.0, contract.daily_hours());
+}
+
+#[test]
+fn daily_and_monthly_hours_are_normalised_per_day() {
+    let daily = serde_json::json!({
+        "working_hours": "750",
+        "working_hours_frequency": "daily",
+        "working_week_days": "monday,tuesday,wednesday,thursday"
+    });
+    let daily = ContractVersion::from_factorial(&daily).unwrap();
+    assert_eq!(7.5, daily.daily_hours());
+    assert_eq!(30.0, daily.weekly_hours());
+
+    let monthly = serde_json::json!({
+        "working_hours": 10400,
+        "working_hours_frequency": "monthly",
+        "working_week_days": "monday,tuesday,wednesday,thursday,friday"
+    });
+    let monthly = ContractVersion::from_factorial(&monthly).unwrap();
+    assert_eq!(4.8, monthly.daily_hours());
+}
+
+#[test]
+fn unknown_hours_frequency_is_an_error() {
+    let contract = serde_json::json!({
+        "working_hours": 4000,
+        "working_hours_frequency": "yearly",
+        "working_week_days": "monday"
+    });
+    assert!(ContractVersion::from_factorial(&contract).is_err());
+}
+
+#[test]
+fn schedule_overrides_shift_duration_on_its_day() {
+    let mut config = Configuration::default();
+    config.shift_duration = 8.0;
+    let schedule: BTreeMap<String, DaySchedule> = serde_json::from_str(
+        r#"{ "friday": { "start": "07:30", "duration": 4.0, "location_type": "work_from_home" } }"#,
+    )
+    .unwrap();
+    config.schedule = schedule;
+    let thursday = NaiveDate::from_ymd_opt(2024, 5, 2).unwrap();
+    let friday = NaiveDate::from_ymd_opt(2024, 5, 3).unwrap();
+    assert!(config.schedule_on(thursday).is_none());
+    assert_eq!(Duration::hours(8), config.daily_target(thursday));
+    assert_eq!(Duration::hours(4), config.daily_target(friday));
+    let friday_schedule = config.schedule_on(friday).unwrap();
+    assert_eq!(
+        chrono::NaiveTime::from_hms_opt(7, 30, 0).unwrap(),
+        friday_schedule.start
+    );
+    assert_eq!(None, friday_schedule.break_window);
+}
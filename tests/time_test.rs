@@ -1,5 +1,11 @@
 use chrono::Datelike;
-use tracktorial::time::{get_break_duration, parse_date, parse_date_time, parse_duration};
+use tracktorial::break_policy::German;
+use tracktorial::config::PlannedBreak;
+use tracktorial::time::{
+    complete_day, end_after, format_duration, free_part_of_day, get_break_duration, names_day,
+    on_day, parse_date, parse_date_time, parse_duration, remaining_work, split_at_midnight,
+    workday_of, working_half, FreeDay, FreeDayReason, HalfDay, LeaveKind, Shift, WorkDay,
+};
 
 #[test]
 fn time_parse_as_hms() {
@@ -46,6 +52,16 @@ fn date_parse_as_ddmmyyyy() {
     assert_eq!(first_jan, parsed_date);
 }
 
+#[test]
+fn date_is_parsed_as_local_midnight() {
+    let day = chrono::NaiveDate::from_ymd_opt(2024, 5, 2).unwrap();
+    for date in ["2024-05-02", "02.05.2024"] {
+        let parsed = parse_date(date).unwrap();
+        assert_eq!(day, parsed.date_naive());
+        assert_eq!(chrono::NaiveTime::MIN, parsed.time());
+    }
+}
+
 #[test]
 fn date_parse_as_yyyymmdd() {
     let parsed_date = parse_date("2024-01-01").unwrap();
@@ -90,9 +106,81 @@ fn duration_parse_as_m() {
 }
 
 #[test]
-fn duration_as_m_cannot_overflow_into_h() {
-    let parsed_duration = parse_duration("120m");
-    assert!(parsed_duration.is_err());
+fn duration_as_m_overflows_into_h() {
+    let parsed_duration = parse_duration("120m").unwrap();
+    assert_eq!(chrono::Duration::hours(2), parsed_duration);
+    let parsed_duration = parse_duration("90m").unwrap();
+    assert_eq!(chrono::Duration::minutes(90), parsed_duration);
+}
+
+#[test]
+fn duration_parse_longer_than_a_day() {
+    let parsed_duration = parse_duration("25h").unwrap();
+    assert_eq!(chrono::Duration::hours(25), parsed_duration);
+}
+
+#[test]
+fn duration_parse_decimals() {
+    assert_eq!(
+        chrono::Duration::minutes(450),
+        parse_duration("7.5h").unwrap()
+    );
+    assert_eq!(
+        chrono::Duration::seconds(90),
+        parse_duration("1.5m").unwrap()
+    );
+    assert_eq!(
+        chrono::Duration::minutes(90),
+        parse_duration("1h 30m").unwrap()
+    );
+}
+
+#[test]
+fn duration_parse_iso_8601() {
+    assert_eq!(
+        chrono::Duration::minutes(450),
+        parse_duration("PT7H30M").unwrap()
+    );
+    assert_eq!(
+        chrono::Duration::hours(26),
+        parse_duration("P1DT2H").unwrap()
+    );
+    assert_eq!(chrono::Duration::weeks(1), parse_duration("P1W").unwrap());
+    assert_eq!(
+        chrono::Duration::seconds(5),
+        parse_duration("pt5s").unwrap()
+    );
+}
+
+#[test]
+fn duration_parse_negative() {
+    assert_eq!(
+        chrono::Duration::minutes(-5),
+        parse_duration("-5m").unwrap()
+    );
+    assert_eq!(
+        chrono::Duration::minutes(-5),
+        parse_duration("-PT5M").unwrap()
+    );
+    assert_eq!(chrono::Duration::minutes(5), parse_duration("+5m").unwrap());
+}
+
+#[test]
+fn duration_rejects_invalid_input() {
+    for invalid in [
+        "",
+        "7",
+        "h",
+        "7x",
+        "1h1h",
+        "P",
+        "PT",
+        "P1M",
+        "1..5h",
+        "99999999999999999h",
+    ] {
+        assert!(parse_duration(invalid).is_err(), "{invalid}");
+    }
 }
 
 #[test]
@@ -114,3 +202,590 @@ fn thirty_min_break_if_working_more_than_nine_h() {
     let break_duration = get_break_duration(parse_duration("9h").unwrap());
     assert_eq!(chrono::Duration::minutes(45), break_duration);
 }
+
+#[test]
+fn half_day_holiday_on_weekend_is_whole_day() {
+    let day = parse_date("2024-12-28").unwrap();
+    let free_days = vec![
+        FreeDay {
+            day,
+            half: HalfDay::EndOfDay,
+            hours: None,
+            reason: FreeDayReason::Holiday("Christmas Eve".to_string()),
+        },
+        FreeDay::from(day),
+    ];
+    assert_eq!(Some(HalfDay::WholeDay), free_part_of_day(&free_days, day));
+}
+
+#[test]
+fn both_halves_free_is_whole_day() {
+    let day = parse_date("2024-12-24").unwrap();
+    let free_days = vec![
+        FreeDay {
+            day,
+            half: HalfDay::StartOfDay,
+            hours: None,
+            reason: FreeDayReason::Holiday("Christmas Eve".to_string()),
+        },
+        FreeDay {
+            day,
+            half: HalfDay::EndOfDay,
+            hours: None,
+            reason: FreeDayReason::Holiday("Christmas Eve".to_string()),
+        },
+    ];
+    assert_eq!(Some(HalfDay::WholeDay), free_part_of_day(&free_days, day));
+    let next_day = parse_date("2024-12-25").unwrap();
+    assert_eq!(None, free_part_of_day(&free_days, next_day));
+}
+
+#[test]
+fn work_the_morning_if_end_of_day_is_free() {
+    let start = parse_date_time("2024-12-24T08:00:00").unwrap();
+    let (half_start, half_duration) = working_half(
+        start,
+        parse_duration("8h").unwrap(),
+        &HalfDay::EndOfDay,
+        &German,
+    )
+    .unwrap();
+    assert_eq!(start, half_start);
+    assert_eq!(chrono::Duration::hours(4), half_duration);
+}
+
+#[test]
+fn work_the_afternoon_if_start_of_day_is_free() {
+    let start = parse_date_time("2024-12-24T08:00:00").unwrap();
+    let (half_start, half_duration) = working_half(
+        start,
+        parse_duration("8h").unwrap(),
+        &HalfDay::StartOfDay,
+        &German,
+    )
+    .unwrap();
+    assert_eq!(parse_date_time("2024-12-24T12:30:00").unwrap(), half_start);
+    assert_eq!(chrono::Duration::hours(4), half_duration);
+    assert!(working_half(
+        start,
+        parse_duration("8h").unwrap(),
+        &HalfDay::WholeDay,
+        &German
+    )
+    .is_none());
+}
+
+#[test]
+fn hourly_absence_in_the_morning_moves_start_back() {
+    let start = parse_date_time("2024-05-02T08:00:00").unwrap();
+    let free_days = vec![FreeDay {
+        day: parse_date("2024-05-02").unwrap(),
+        half: HalfDay::StartOfDay,
+        hours: Some(chrono::Duration::hours(2)),
+        reason: FreeDayReason::Leave {
+            kind: LeaveKind::Other("Doctor's appointment".to_string()),
+            approved: true,
+        },
+    }];
+    let (work_start, work_duration) =
+        remaining_work(start, parse_duration("8h").unwrap(), &free_days, &German).unwrap();
+    assert_eq!(parse_date_time("2024-05-02T10:00:00").unwrap(), work_start);
+    assert_eq!(chrono::Duration::hours(6), work_duration);
+}
+
+#[test]
+fn hourly_absence_in_the_afternoon_ends_shift_early() {
+    let start = parse_date_time("2024-05-02T08:00:00").unwrap();
+    let free_days = vec![FreeDay {
+        day: parse_date("2024-05-02").unwrap(),
+        half: HalfDay::EndOfDay,
+        hours: Some(parse_duration("1h30m").unwrap()),
+        reason: FreeDayReason::Leave {
+            kind: LeaveKind::Other("Doctor's appointment".to_string()),
+            approved: true,
+        },
+    }];
+    let (work_start, work_duration) =
+        remaining_work(start, parse_duration("8h").unwrap(), &free_days, &German).unwrap();
+    assert_eq!(start, work_start);
+    assert_eq!(parse_duration("6h30m").unwrap(), work_duration);
+}
+
+#[test]
+fn no_work_left_if_absence_covers_the_shift() {
+    let start = parse_date_time("2024-05-02T08:00:00").unwrap();
+    let free_days = vec![FreeDay {
+        day: parse_date("2024-05-02").unwrap(),
+        half: HalfDay::EndOfDay,
+        hours: Some(chrono::Duration::hours(4)),
+        reason: FreeDayReason::Leave {
+            kind: LeaveKind::Other("Doctor's appointment".to_string()),
+            approved: true,
+        },
+    }];
+    assert!(remaining_work(start, parse_duration("4h").unwrap(), &free_days, &German).is_none());
+}
+
+#[test]
+fn leave_kind_from_leave_type_name() {
+    assert_eq!(LeaveKind::Vacation, LeaveKind::from_name("Vacation"));
+    assert_eq!(LeaveKind::Vacation, LeaveKind::from_name("Urlaub"));
+    assert_eq!(LeaveKind::SickLeave, LeaveKind::from_name("Sick leave"));
+    assert_eq!(LeaveKind::Parental, LeaveKind::from_name("Maternity leave"));
+    assert_eq!(LeaveKind::Training, LeaveKind::from_name("Fortbildung"));
+    assert_eq!(
+        LeaveKind::Other("Wedding".to_string()),
+        LeaveKind::from_name("Wedding")
+    );
+}
+
+#[test]
+fn pending_leave_is_pending() {
+    let mut free_day = FreeDay::from(parse_date("2024-05-02").unwrap());
+    assert!(!free_day.is_pending());
+    free_day.reason = FreeDayReason::Leave {
+        kind: LeaveKind::Vacation,
+        approved: false,
+    };
+    assert!(free_day.is_pending());
+    assert_eq!("Vacation (pending approval)", free_day.reason.to_string());
+}
+
+#[test]
+fn durations_are_formatted_as_hours_and_minutes() {
+    assert_eq!("7h30m", format_duration(parse_duration("7h30m").unwrap()));
+    assert_eq!("-0h15m", format_duration(chrono::Duration::minutes(-15)));
+}
+
+#[test]
+fn break_is_moved_into_window() {
+    let start = parse_date_time("2024-05-02T07:00:00").unwrap();
+    let mut work_day = WorkDay::standard_shift(start, parse_duration("8h").unwrap(), &German);
+    assert_eq!(
+        parse_date_time("2024-05-02T11:00:00").unwrap(),
+        work_day.breaks().next().unwrap().start
+    );
+    work_day.move_break_into(
+        parse_date_time("2024-05-02T12:00:00").unwrap(),
+        parse_date_time("2024-05-02T13:30:00").unwrap(),
+    );
+    let lunch = work_day.breaks().next().unwrap();
+    assert_eq!(parse_date_time("2024-05-02T12:00:00").unwrap(), lunch.start);
+    assert_eq!(parse_date_time("2024-05-02T12:30:00").unwrap(), lunch.end);
+    assert_eq!(
+        parse_date_time("2024-05-02T15:30:00").unwrap(),
+        work_day.clock_out()
+    );
+}
+
+#[test]
+fn no_break_segment_if_no_break_is_required() {
+    let start = parse_date_time("2024-05-02T08:00:00").unwrap();
+    let work_day = WorkDay::standard_shift(start, parse_duration("4h").unwrap(), &German);
+    assert_eq!(1, work_day.segments.len());
+    assert_eq!(start, work_day.clock_in());
+    assert_eq!(
+        parse_date_time("2024-05-02T12:00:00").unwrap(),
+        work_day.clock_out()
+    );
+}
+
+#[test]
+fn configured_breaks_are_taken_in_order() {
+    let start = parse_date_time("2024-05-02T08:00:00").unwrap();
+    let breaks = vec![
+        PlannedBreak {
+            after_hours: 4.5,
+            minutes: 30,
+        },
+        PlannedBreak {
+            after_hours: 2.0,
+            minutes: 15,
+        },
+    ];
+    let work_day = WorkDay::plan(start, parse_duration("8h").unwrap(), &breaks, &German);
+    let times: Vec<(String, String, bool)> = work_day
+        .segments
+        .iter()
+        .map(|s| {
+            (
+                s.start.format("%H:%M").to_string(),
+                s.end.format("%H:%M").to_string(),
+                s.is_break,
+            )
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            ("08:00".to_string(), "10:00".to_string(), false),
+            ("10:00".to_string(), "10:15".to_string(), true),
+            ("10:15".to_string(), "12:45".to_string(), false),
+            ("12:45".to_string(), "13:15".to_string(), true),
+            ("13:15".to_string(), "16:45".to_string(), false),
+        ],
+        times
+    );
+}
+
+#[test]
+fn last_break_is_extended_to_the_required_length() {
+    let start = parse_date_time("2024-05-02T08:00:00").unwrap();
+    let breaks = vec![
+        PlannedBreak {
+            after_hours: 2.0,
+            minutes: 10,
+        },
+        PlannedBreak {
+            after_hours: 5.0,
+            minutes: 15,
+        },
+    ];
+    let work_day = WorkDay::plan(start, parse_duration("9h").unwrap(), &breaks, &German);
+    let lengths: Vec<chrono::Duration> = work_day.breaks().map(|b| b.end - b.start).collect();
+    assert_eq!(
+        vec![chrono::Duration::minutes(10), chrono::Duration::minutes(35)],
+        lengths
+    );
+    assert_eq!(
+        parse_date_time("2024-05-02T17:45:00").unwrap(),
+        work_day.clock_out()
+    );
+}
+
+#[test]
+fn break_is_placed_in_lunch_window() {
+    let start = parse_date_time("2024-05-02T07:00:00").unwrap();
+    let mut work_day = WorkDay::standard_shift(start, parse_duration("7h30m").unwrap(), &German);
+    work_day.place_break_in_window(
+        parse_date_time("2024-05-02T12:00:00").unwrap(),
+        parse_date_time("2024-05-02T13:30:00").unwrap(),
+        &German,
+    );
+    let lunch = work_day.breaks().next().unwrap();
+    assert_eq!(parse_date_time("2024-05-02T12:00:00").unwrap(), lunch.start);
+    assert_eq!(parse_date_time("2024-05-02T12:30:00").unwrap(), lunch.end);
+}
+
+#[test]
+fn break_falls_back_to_latest_legal_point_outside_of_window() {
+    let start = parse_date_time("2024-05-02T14:00:00").unwrap();
+    let mut work_day = WorkDay::standard_shift(start, parse_duration("8h").unwrap(), &German);
+    work_day.place_break_in_window(
+        parse_date_time("2024-05-02T12:00:00").unwrap(),
+        parse_date_time("2024-05-02T13:30:00").unwrap(),
+        &German,
+    );
+    let lunch = work_day.breaks().next().unwrap();
+    assert_eq!(parse_date_time("2024-05-02T20:00:00").unwrap(), lunch.start);
+    assert_eq!(
+        parse_date_time("2024-05-02T22:30:00").unwrap(),
+        work_day.clock_out()
+    );
+}
+
+#[test]
+fn break_in_window_must_not_exceed_six_h_of_work() {
+    let start = parse_date_time("2024-05-02T05:00:00").unwrap();
+    let mut work_day = WorkDay::standard_shift(start, parse_duration("8h").unwrap(), &German);
+    work_day.place_break_in_window(
+        parse_date_time("2024-05-02T12:00:00").unwrap(),
+        parse_date_time("2024-05-02T13:30:00").unwrap(),
+        &German,
+    );
+    let lunch = work_day.breaks().next().unwrap();
+    assert_eq!(parse_date_time("2024-05-02T11:00:00").unwrap(), lunch.start);
+}
+
+fn segment_times(segments: &[tracktorial::time::Segment]) -> Vec<(String, String, bool)> {
+    segments
+        .iter()
+        .map(|s| {
+            (
+                s.start.format("%H:%M").to_string(),
+                s.end.format("%H:%M").to_string(),
+                s.is_break,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn open_shift_is_completed_with_break_and_clock_out() {
+    let recorded = vec![Shift {
+        id: 1,
+        start: parse_date_time("2024-05-02T08:12:00").unwrap(),
+        end: None,
+        is_break: false,
+    }];
+    let segments = complete_day(&recorded, parse_duration("8h").unwrap(), &German);
+    assert_eq!(
+        vec![
+            ("08:12".to_string(), "12:12".to_string(), false),
+            ("12:12".to_string(), "12:42".to_string(), true),
+            ("12:42".to_string(), "16:42".to_string(), false),
+        ],
+        segment_times(&segments)
+    );
+}
+
+#[test]
+fn recorded_break_is_not_taken_again() {
+    let recorded = vec![
+        Shift {
+            id: 1,
+            start: parse_date_time("2024-05-02T08:00:00").unwrap(),
+            end: Some(parse_date_time("2024-05-02T12:00:00").unwrap()),
+            is_break: false,
+        },
+        Shift {
+            id: 2,
+            start: parse_date_time("2024-05-02T12:00:00").unwrap(),
+            end: Some(parse_date_time("2024-05-02T12:30:00").unwrap()),
+            is_break: true,
+        },
+    ];
+    let segments = complete_day(&recorded, parse_duration("8h").unwrap(), &German);
+    assert_eq!(
+        vec![("12:30".to_string(), "16:30".to_string(), false)],
+        segment_times(&segments)
+    );
+}
+
+#[test]
+fn complete_day_adds_nothing() {
+    let recorded = vec![Shift {
+        id: 1,
+        start: parse_date_time("2024-05-02T08:00:00").unwrap(),
+        end: Some(parse_date_time("2024-05-02T12:00:00").unwrap()),
+        is_break: false,
+    }];
+    assert!(complete_day(&recorded, parse_duration("4h").unwrap(), &German).is_empty());
+    assert!(complete_day(&[], parse_duration("4h").unwrap(), &German).is_empty());
+}
+
+#[test]
+fn recorded_work_counts_towards_max_work_without_break() {
+    let recorded = vec![Shift {
+        id: 1,
+        start: parse_date_time("2024-05-02T08:00:00").unwrap(),
+        end: Some(parse_date_time("2024-05-02T13:00:00").unwrap()),
+        is_break: false,
+    }];
+    let segments = complete_day(&recorded, parse_duration("8h").unwrap(), &German);
+    assert_eq!(
+        vec![
+            ("13:00".to_string(), "14:00".to_string(), false),
+            ("14:00".to_string(), "14:30".to_string(), true),
+            ("14:30".to_string(), "16:30".to_string(), false),
+        ],
+        segment_times(&segments)
+    );
+
+    let recorded = vec![Shift {
+        id: 1,
+        start: parse_date_time("2024-05-02T08:00:00").unwrap(),
+        end: Some(parse_date_time("2024-05-02T14:00:00").unwrap()),
+        is_break: false,
+    }];
+    let segments = complete_day(&recorded, parse_duration("8h").unwrap(), &German);
+    assert_eq!(
+        vec![
+            ("14:00".to_string(), "14:30".to_string(), true),
+            ("14:30".to_string(), "16:30".to_string(), false),
+        ],
+        segment_times(&segments)
+    );
+}
+
+#[test]
+fn complete_day_adds_no_empty_segments() {
+    let recorded = vec![
+        Shift {
+            id: 1,
+            start: parse_date_time("2024-05-02T08:00:00").unwrap(),
+            end: Some(parse_date_time("2024-05-02T12:00:00").unwrap()),
+            is_break: false,
+        },
+        Shift {
+            id: 2,
+            start: parse_date_time("2024-05-02T12:00:00").unwrap(),
+            end: Some(parse_date_time("2024-05-02T12:30:00").unwrap()),
+            is_break: true,
+        },
+        Shift {
+            id: 3,
+            start: parse_date_time("2024-05-02T12:30:00").unwrap(),
+            end: Some(parse_date_time("2024-05-02T14:00:00").unwrap()),
+            is_break: false,
+        },
+        Shift {
+            id: 4,
+            start: parse_date_time("2024-05-02T14:00:00").unwrap(),
+            end: None,
+            is_break: true,
+        },
+    ];
+    let segments = complete_day(&recorded, parse_duration("8h").unwrap(), &German);
+    assert_eq!(
+        vec![("14:00".to_string(), "16:30".to_string(), false)],
+        segment_times(&segments)
+    );
+}
+
+#[test]
+fn split_night_shift_at_midnight() {
+    let start = parse_date_time("2024-05-02T22:00:00").unwrap();
+    let end = parse_date_time("2024-05-03T06:00:00").unwrap();
+    let midnight = parse_date_time("2024-05-03T00:00:00").unwrap();
+    assert_eq!(
+        vec![(start, midnight), (midnight, end)],
+        split_at_midnight(start, end)
+    );
+}
+
+#[test]
+fn night_shifts_belong_to_the_day_they_start_on() {
+    let booked = |start: &str, end: &str, is_break: bool| Shift {
+        id: 0,
+        start: parse_date_time(start).unwrap(),
+        end: Some(parse_date_time(end).unwrap()),
+        is_break,
+    };
+    let shifts = vec![
+        booked("2024-05-02T22:00:00", "2024-05-03T00:00:00", false),
+        booked("2024-05-03T00:00:00", "2024-05-03T02:00:00", false),
+        booked("2024-05-03T02:00:00", "2024-05-03T02:30:00", true),
+        booked("2024-05-03T02:30:00", "2024-05-03T06:30:00", false),
+        booked("2024-05-03T22:00:00", "2024-05-04T00:00:00", false),
+        booked("2024-05-04T00:00:00", "2024-05-04T02:00:00", false),
+        booked("2024-05-04T02:00:00", "2024-05-04T02:30:00", true),
+        booked("2024-05-04T02:30:00", "2024-05-04T06:30:00", false),
+    ];
+    let workdays: Vec<String> = shifts
+        .iter()
+        .map(|s| workday_of(s, &shifts).format("%Y-%m-%d").to_string())
+        .collect();
+    assert_eq!(
+        vec![
+            "2024-05-02",
+            "2024-05-02",
+            "2024-05-02",
+            "2024-05-02",
+            "2024-05-03",
+            "2024-05-03",
+            "2024-05-03",
+            "2024-05-03",
+        ],
+        workdays
+    );
+}
+
+#[test]
+fn split_day_shift_not_at_all() {
+    let start = parse_date_time("2024-05-02T08:00:00").unwrap();
+    let end = parse_date_time("2024-05-02T16:00:00").unwrap();
+    assert_eq!(vec![(start, end)], split_at_midnight(start, end));
+}
+
+#[test]
+fn end_before_start_is_on_next_day() {
+    let start = parse_date_time("2024-05-02T22:00:00").unwrap();
+    assert_eq!(
+        parse_date_time("2024-05-03T06:00:00").unwrap(),
+        end_after(start, parse_date_time("2024-05-02T06:00:00").unwrap()).unwrap()
+    );
+    let end = parse_date_time("2024-05-02T23:00:00").unwrap();
+    assert_eq!(end, end_after(start, end).unwrap());
+}
+
+#[test]
+fn parse_yesterday_and_today() {
+    let today = chrono::Local::now().date_naive();
+    assert_eq!(today, parse_date("today").unwrap().date_naive());
+    assert_eq!(
+        today.pred_opt().unwrap(),
+        parse_date("yesterday").unwrap().date_naive()
+    );
+}
+
+#[test]
+fn time_on_other_day() {
+    let time = parse_date_time("17:45").unwrap();
+    let day = parse_date("2024-05-02").unwrap();
+    assert_eq!(
+        parse_date_time("2024-05-02T17:45:00").unwrap(),
+        on_day(time, day).unwrap()
+    );
+}
+
+#[test]
+fn times_that_name_their_day() {
+    assert!(names_day("2024-05-02T17:45:00"));
+    assert!(names_day("yesterday 17:45"));
+    assert!(names_day("Friday 9:00"));
+    assert!(names_day("now"));
+    assert!(names_day("now-5m"));
+    assert!(names_day("-10m"));
+    assert!(names_day("+1h"));
+    assert!(!names_day("17:45"));
+    assert!(!names_day("5:30pm"));
+    assert!(!names_day("9 AM"));
+}
+
+#[test]
+fn parse_time_relative_to_now() {
+    let before = chrono::Local::now();
+    let parsed = parse_date_time("now-5m").unwrap();
+    let after = chrono::Local::now();
+    assert!(before - chrono::Duration::minutes(5) <= parsed);
+    assert!(parsed <= after - chrono::Duration::minutes(5));
+    let parsed = parse_date_time("-10m").unwrap();
+    assert!(parsed <= chrono::Local::now() - chrono::Duration::minutes(10));
+    let parsed = parse_date_time("+1h").unwrap();
+    assert!(parsed > chrono::Local::now() + chrono::Duration::minutes(59));
+}
+
+#[test]
+fn parse_time_with_am_pm() {
+    let today = chrono::Local::now();
+    let at = |h, m| {
+        today
+            .with_time(chrono::NaiveTime::from_hms_opt(h, m, 0).unwrap())
+            .unwrap()
+    };
+    assert_eq!(at(17, 30), parse_date_time("5:30pm").unwrap());
+    assert_eq!(at(9, 0), parse_date_time("9 AM").unwrap());
+    assert_eq!(at(0, 15), parse_date_time("12:15am").unwrap());
+    assert_eq!(at(12, 0), parse_date_time("12pm").unwrap());
+    assert!(parse_date_time("13:00pm").is_err());
+}
+
+#[test]
+fn parse_time_on_named_day() {
+    let today = chrono::Local::now();
+    let yesterday = today.checked_sub_days(chrono::Days::new(1)).unwrap();
+    let at_1745 = chrono::NaiveTime::from_hms_opt(17, 45, 0).unwrap();
+    assert_eq!(
+        yesterday.with_time(at_1745).unwrap(),
+        parse_date_time("yesterday 17:45").unwrap()
+    );
+    let weekday = format!("{} 17:45", today.weekday());
+    assert_eq!(
+        today.with_time(at_1745).unwrap(),
+        parse_date_time(&weekday).unwrap()
+    );
+    let weekday = format!("{} 17:45", yesterday.weekday());
+    assert_eq!(
+        yesterday.with_time(at_1745).unwrap(),
+        parse_date_time(&weekday).unwrap()
+    );
+}
+
+#[test]
+fn parse_time_rejects_unclear_input() {
+    assert!(parse_date_time("yesterday").is_err());
+    assert!(parse_date_time("someday 17:45").is_err());
+    assert!(parse_date_time("now*5m").is_err());
+    assert!(parse_date_time("-").is_err());
+    assert!(parse_date_time("half past five").is_err());
+}